@@ -0,0 +1,30 @@
+use super::dir_of;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// A crate in the project: its source directory and the name other crates
+/// (and its own binaries) import it by.
+#[derive(Debug, Clone)]
+pub struct Crate {
+    pub src: PathBuf,
+    pub name: String,
+}
+
+/// Every crate with a `Cargo.toml` among `files`.
+pub fn crates(root: &Path, files: &[PathBuf]) -> Vec<Crate> {
+    files
+        .iter()
+        .filter(|f| f.file_name().is_some_and(|n| n == "Cargo.toml"))
+        .filter_map(|manifest| {
+            let text = fs::read_to_string(root.join(manifest)).ok()?;
+            let value: toml::Value = toml::from_str(&text).ok()?;
+            let name = value.get("package")?.get("name")?.as_str()?;
+            Some(Crate {
+                src: dir_of(manifest).join("src"),
+                name: name.replace('-', "_"),
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,113 @@
+use super::{dir_of, regex, strip_comments, Project};
+use regex::Regex;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+/// The `module` line of the root `go.mod`.
+pub fn module_path(root: &Path) -> Option<String> {
+    let text = fs::read_to_string(root.join("go.mod")).ok()?;
+    text.lines()
+        .find_map(|l| l.trim().strip_prefix("module "))
+        .map(|m| m.trim().trim_matches('"').to_string())
+}
+
+/// Imports inside the module, each standing for every non-test file of
+/// the imported package's folder.
+pub fn imports(file: &Path, text: &str, project: &Project) -> Vec<PathBuf> {
+    static BLOCK: OnceLock<Option<Regex>> = OnceLock::new();
+    static SPEC: OnceLock<Option<Regex>> = OnceLock::new();
+    let Some(module) = project.go_module.as_deref() else {
+        return Vec::new();
+    };
+    let (Some(block), Some(spec)) = (
+        regex(
+            &BLOCK,
+            r#"(?s)\bimport\s*\(([^)]*)\)|\bimport\s+(?:\w+\s+|\.\s+|_\s+)?"[^"]*""#,
+        ),
+        regex(&SPEC, r#""([^"]+)""#),
+    ) else {
+        return Vec::new();
+    };
+    let text = strip_comments(text, &['"', '`']);
+    let mut out = Vec::new();
+    for found in block.find_iter(&text) {
+        for cap in spec.captures_iter(found.as_str()) {
+            let Some(rel) = cap.get(1).and_then(|m| package_dir(m.as_str(), module)) else {
+                continue;
+            };
+            out.extend(package_files(Path::new(rel), file, project));
+        }
+    }
+    out
+}
+
+/// Folder of an import path inside `module`: `example.com/app/util` in
+/// module `example.com/app` is `util`, while `example.com/apps` is outside.
+fn package_dir<'a>(import: &'a str, module: &str) -> Option<&'a str> {
+    match import.strip_prefix(module)? {
+        "" => Some(""),
+        rest => rest.strip_prefix('/'),
+    }
+}
+
+fn package_files(dir: &Path, from: &Path, project: &Project) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = project
+        .files
+        .iter()
+        .filter(|f| dir_of(f) == dir && f.as_path() != from)
+        .filter(|f| {
+            let name = f.to_string_lossy();
+            name.ends_with(".go") && !name.ends_with("_test.go")
+        })
+        .cloned()
+        .collect();
+    files.sort();
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn module_prefix_is_stripped_only_at_a_path_boundary() {
+        assert_eq!(
+            package_dir("example.com/app/util", "example.com/app"),
+            Some("util")
+        );
+        assert_eq!(package_dir("example.com/app", "example.com/app"), Some(""));
+        assert_eq!(
+            package_dir("example.com/apps/util", "example.com/app"),
+            None
+        );
+        assert_eq!(package_dir("fmt", "example.com/app"), None);
+    }
+
+    #[test]
+    fn imports_stand_for_the_package_files() {
+        let project = Project {
+            files: [
+                "cmd/main.go",
+                "internal/util/a.go",
+                "internal/util/a_test.go",
+                "internal/util/b.go",
+            ]
+            .iter()
+            .map(PathBuf::from)
+            .collect(),
+            rust_crates: Vec::new(),
+            go_module: Some("example.com/app".to_string()),
+        };
+        let text = "import (\n\t\"fmt\"\n\tu \"example.com/app/internal/util\"\n\t// \"example.com/app/gone\"\n)\n";
+        assert_eq!(
+            imports(Path::new("cmd/main.go"), text, &project),
+            [
+                PathBuf::from("internal/util/a.go"),
+                "internal/util/b.go".into()
+            ]
+        );
+    }
+}
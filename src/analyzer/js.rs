@@ -0,0 +1,85 @@
+use super::{dir_of, normalize, regex, strip_comments, Project};
+use regex::Regex;
+use std::{
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+/// Extensions tried, in order, for a specifier written without one.
+const EXTS: &[&str] = &["ts", "tsx", "js", "jsx", "mjs", "cjs", "mts", "cts"];
+
+/// Relative `import`/`export ... from`, side-effect imports, dynamic
+/// `import()` and `require()`. Package imports are outside the project.
+pub fn imports(file: &Path, text: &str, project: &Project) -> Vec<PathBuf> {
+    static SPEC: OnceLock<Option<Regex>> = OnceLock::new();
+    let Some(re) = regex(
+        &SPEC,
+        r#"(?:\bfrom\s*|\bimport\s*\(?\s*|\brequire\s*\(\s*)['"]([^'"]+)['"]"#,
+    ) else {
+        return Vec::new();
+    };
+    let text = strip_comments(text, &['"', '\'', '`']);
+    re.captures_iter(&text)
+        .filter_map(|cap| cap.get(1))
+        .filter_map(|spec| resolve(file, spec.as_str(), project))
+        .collect()
+}
+
+fn resolve(file: &Path, spec: &str, project: &Project) -> Option<PathBuf> {
+    if !spec.starts_with("./") && !spec.starts_with("../") {
+        return None;
+    }
+    let base = normalize(&dir_of(file).join(spec))?;
+    let mut candidates = vec![base.clone()];
+    // TypeScript ESM imports name the emitted `.js` file.
+    if let Some(stem) = spec
+        .strip_suffix(".js")
+        .or_else(|| spec.strip_suffix(".jsx"))
+        .or_else(|| spec.strip_suffix(".mjs"))
+    {
+        let stem = normalize(&dir_of(file).join(stem))?;
+        candidates.extend(["ts", "tsx", "mts"].iter().map(|e| with_ext(&stem, e)));
+    }
+    candidates.extend(EXTS.iter().map(|e| with_ext(&base, e)));
+    candidates.extend(EXTS.iter().map(|e| base.join(format!("index.{e}"))));
+    project.first(candidates)
+}
+
+/// `a/b` + `ts` is `a/b.ts`, keeping dots already in the name (`a/b.config`).
+fn with_ext(path: &Path, ext: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".");
+    name.push(ext);
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(files: &[&str]) -> Project {
+        Project {
+            files: files.iter().map(PathBuf::from).collect(),
+            rust_crates: Vec::new(),
+            go_module: None,
+        }
+    }
+
+    #[test]
+    fn js_specifier_finds_the_typescript_source() {
+        let p = project(&["src/a.ts", "src/b.ts", "src/lib/index.tsx"]);
+        let text =
+            "import { b } from './b.js';\nimport lib from \"./lib\";\n// import c from './c';\n";
+        assert_eq!(
+            imports(Path::new("src/a.ts"), text, &p),
+            [PathBuf::from("src/b.ts"), "src/lib/index.tsx".into()]
+        );
+    }
+
+    #[test]
+    fn packages_and_escaping_paths_are_skipped() {
+        let p = project(&["a.js"]);
+        assert!(resolve(Path::new("a.js"), "react", &p).is_none());
+        assert!(resolve(Path::new("a.js"), "../outside", &p).is_none());
+    }
+}
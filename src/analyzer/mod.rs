@@ -0,0 +1,253 @@
+mod cargo;
+mod go;
+mod js;
+mod python;
+mod rust;
+
+use crate::semmap::{DepEdge, Layer, SemmapData};
+use regex::Regex;
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Component, Path, PathBuf},
+    sync::{
+        mpsc::{self, Receiver, TryRecvError},
+        OnceLock,
+    },
+    thread,
+};
+
+/// Files larger than this are generated or vendored more often than not.
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+
+/// What the language resolvers may look up: every scanned file, by
+/// root-relative path, the Rust crates and the Go module path from `go.mod`.
+pub struct Project {
+    pub files: HashSet<PathBuf>,
+    pub rust_crates: Vec<cargo::Crate>,
+    pub go_module: Option<String>,
+}
+
+impl Project {
+    pub fn has(&self, path: &Path) -> bool {
+        self.files.contains(path)
+    }
+
+    /// The first candidate that is a scanned file.
+    pub fn first(&self, candidates: impl IntoIterator<Item = PathBuf>) -> Option<PathBuf> {
+        candidates.into_iter().find(|p| self.has(p))
+    }
+}
+
+/// Import graph of the source files in `files`, shaped like a SEMMAP with a
+/// single layer so subsystem clustering and dependency closure apply.
+/// `None` when no file imports another.
+pub fn analyze(root: &Path, files: &[PathBuf]) -> Option<SemmapData> {
+    let project = Project {
+        files: files.iter().cloned().collect(),
+        rust_crates: cargo::crates(root, files),
+        go_module: go::module_path(root),
+    };
+    let mut sources = Vec::new();
+    let mut edges = Vec::new();
+    for file in files {
+        let Some(mut imports) = imports_of(root, file, &project) else {
+            continue;
+        };
+        sources.push(file.clone());
+        imports.sort();
+        imports.dedup();
+        edges.extend(
+            imports
+                .into_iter()
+                .filter(|to| to != file)
+                .map(|to| DepEdge {
+                    from: file.clone(),
+                    to,
+                }),
+        );
+    }
+    if edges.is_empty() {
+        return None;
+    }
+    let project_name = root
+        .file_name()
+        .map_or_else(String::new, |n| n.to_string_lossy().into_owned());
+    Some(SemmapData {
+        project_name,
+        layers: vec![Layer {
+            index: 0,
+            label: "Source".to_string(),
+            files: sources,
+            notes: HashMap::new(),
+        }],
+        edges,
+        legend: Vec::new(),
+    })
+}
+
+/// [`analyze`] running on its own thread, so a large tree opens at once.
+#[derive(Debug)]
+pub struct Pending {
+    rx: Receiver<Option<SemmapData>>,
+    /// Subsystems a session or profile enabled before the graph arrived.
+    pub wanted: Option<Vec<String>>,
+}
+
+impl Pending {
+    pub fn spawn(root: PathBuf, files: Vec<PathBuf>) -> Self {
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let _ = tx.send(analyze(&root, &files));
+        });
+        Self { rx, wanted: None }
+    }
+
+    /// The graph once the thread is done, `None` while it still runs.
+    pub fn try_take(&self) -> Option<Option<SemmapData>> {
+        match self.rx.try_recv() {
+            Ok(graph) => Some(graph),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(None),
+        }
+    }
+
+    pub fn wait(self) -> Option<SemmapData> {
+        self.rx.recv().ok().flatten()
+    }
+}
+
+/// Files `file` imports; `None` when it is not a language pakr can read.
+pub fn imports_of(root: &Path, file: &Path, project: &Project) -> Option<Vec<PathBuf>> {
+    let ext = file.extension()?.to_str()?;
+    let parse: fn(&Path, &str, &Project) -> Vec<PathBuf> = match ext {
+        "rs" => rust::imports,
+        "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" | "mts" | "cts" => js::imports,
+        "py" => python::imports,
+        "go" => go::imports,
+        _ => return None,
+    };
+    let abs = root.join(file);
+    if fs::metadata(&abs).map_or(true, |m| m.len() > MAX_FILE_BYTES) {
+        return Some(Vec::new());
+    }
+    let text = fs::read_to_string(abs).ok()?;
+    Some(parse(file, &text, project))
+}
+
+/// Resolve `.` and `..` lexically; `None` when the path climbs out of the root.
+pub fn normalize(path: &Path) -> Option<PathBuf> {
+    let mut out = PathBuf::new();
+    for part in path.components() {
+        match part {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !out.pop() {
+                    return None;
+                }
+            }
+            Component::Normal(p) => out.push(p),
+            Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    Some(out)
+}
+
+/// Compiled once per pattern; a pattern that fails to compile matches nothing.
+pub fn regex(cell: &'static OnceLock<Option<Regex>>, pattern: &str) -> Option<&'static Regex> {
+    cell.get_or_init(|| Regex::new(pattern).ok()).as_ref()
+}
+
+/// Drop `//` and `/* */` comments so commented-out imports do not count.
+/// Text between `quotes` is copied as is, so `//` inside a string does not
+/// start a comment; newlines inside block comments are kept so line-based
+/// patterns still see the same lines.
+pub fn strip_comments(text: &str, quotes: &[char]) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '/' if chars.peek() == Some(&'/') => {
+                if chars.any(|c| c == '\n') {
+                    out.push('\n');
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = '\0';
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        out.push('\n');
+                    } else if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+            }
+            q if quotes.contains(&q) => {
+                out.push(q);
+                while let Some(c) = chars.next() {
+                    out.push(c);
+                    if c == '\\' {
+                        out.extend(chars.next());
+                    } else if c == q {
+                        break;
+                    }
+                }
+            }
+            // A Rust char literal such as '"' must not open a string.
+            '\'' if !quotes.contains(&'\'') => {
+                out.push(c);
+                let mut ahead = chars.clone();
+                let len = match ahead.next() {
+                    Some('\\') => 3,
+                    Some(_) => 2,
+                    None => 0,
+                };
+                if len > 0 && chars.clone().nth(len - 1) == Some('\'') {
+                    out.extend(chars.by_ref().take(len));
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Directory holding `file`, empty for files at the root.
+pub fn dir_of(file: &Path) -> &Path {
+    file.parent().unwrap_or_else(|| Path::new(""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_line_and_block_comments() {
+        let text = "use a; // use b;\n/* use c;\nuse d; */ use e;\n";
+        assert_eq!(strip_comments(text, &['"']), "use a; \n\n use e;\n");
+    }
+
+    #[test]
+    fn keeps_comment_markers_inside_strings() {
+        let text = "let u = \"http://x/*y\"; use a;\nlet q = '\"'; use b; // c";
+        assert_eq!(
+            strip_comments(text, &['"']),
+            "let u = \"http://x/*y\"; use a;\nlet q = '\"'; use b; "
+        );
+        assert_eq!(
+            strip_comments("import x from './a' // './b'", &['"', '\'']),
+            "import x from './a' "
+        );
+    }
+
+    #[test]
+    fn normalize_stays_inside_the_root() {
+        assert_eq!(
+            normalize(Path::new("src/a/../b/./c.rs")),
+            Some(PathBuf::from("src/b/c.rs"))
+        );
+        assert_eq!(normalize(Path::new("../x")), None);
+    }
+}
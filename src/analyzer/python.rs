@@ -0,0 +1,141 @@
+use super::{dir_of, regex, Project};
+use regex::Regex;
+use std::{
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+/// `import a.b` and `from a.b import c` (absolute or relative). Absolute
+/// modules are looked up from each folder above the file, so packages work
+/// whether the import root is the project, `src/` or a subfolder.
+pub fn imports(file: &Path, text: &str, project: &Project) -> Vec<PathBuf> {
+    static IMPORT: OnceLock<Option<Regex>> = OnceLock::new();
+    static FROM: OnceLock<Option<Regex>> = OnceLock::new();
+    let text: String = text
+        .lines()
+        .map(|l| l.split('#').next().unwrap_or(l))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mut out = Vec::new();
+    if let Some(re) = regex(&IMPORT, r"(?m)^\s*import\s+([\w., ]+)$") {
+        for cap in re.captures_iter(&text) {
+            let names = cap.get(1).map_or("", |m| m.as_str());
+            out.extend(
+                names
+                    .split(',')
+                    .filter_map(|n| absolute(file, bare(n), project)),
+            );
+        }
+    }
+    if let Some(re) = regex(
+        &FROM,
+        r"(?m)^\s*from\s+(\.*)([\w.]*)\s+import\s+\(?([\w, ]*)",
+    ) {
+        for cap in re.captures_iter(&text) {
+            let dots = cap.get(1).map_or(0, |m| m.as_str().len());
+            let module = cap.get(2).map_or("", |m| m.as_str());
+            let names = cap.get(3).map_or("", |m| m.as_str());
+            out.extend(from_import(file, dots, module, names, project));
+        }
+    }
+    out
+}
+
+/// `from m import x, y`: each name that is a submodule, else `m` itself.
+fn from_import(
+    file: &Path,
+    dots: usize,
+    module: &str,
+    names: &str,
+    project: &Project,
+) -> Vec<PathBuf> {
+    let find = |m: &str| {
+        if dots == 0 {
+            absolute(file, m, project)
+        } else {
+            relative(file, dots, m, project)
+        }
+    };
+    let mut out: Vec<PathBuf> = names
+        .split(',')
+        .map(bare)
+        .filter(|n| !n.is_empty())
+        .filter_map(|n| find(&join(module, n)))
+        .collect();
+    if out.is_empty() {
+        out.extend(find(module));
+    }
+    out
+}
+
+fn absolute(file: &Path, module: &str, project: &Project) -> Option<PathBuf> {
+    if module.is_empty() {
+        return None;
+    }
+    dir_of(file)
+        .ancestors()
+        .chain([Path::new("src")])
+        .find_map(|root| module_file(root, module, project))
+}
+
+/// `from ..pkg import x`: one dot is the file's own package, each extra dot one level up.
+fn relative(file: &Path, dots: usize, module: &str, project: &Project) -> Option<PathBuf> {
+    let base = dir_of(file).ancestors().nth(dots - 1)?;
+    if module.is_empty() {
+        return project.first([base.join("__init__.py")]);
+    }
+    module_file(base, module, project)
+}
+
+fn module_file(root: &Path, module: &str, project: &Project) -> Option<PathBuf> {
+    let path = module.split('.').fold(root.to_path_buf(), |p, s| p.join(s));
+    project.first([path.with_extension("py"), path.join("__init__.py")])
+}
+
+/// `a.b as c` is `a.b`.
+fn bare(name: &str) -> &str {
+    name.split(" as ").next().unwrap_or(name).trim()
+}
+
+fn join(module: &str, name: &str) -> String {
+    if module.is_empty() {
+        name.to_string()
+    } else {
+        format!("{module}.{name}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(files: &[&str]) -> Project {
+        Project {
+            files: files.iter().map(PathBuf::from).collect(),
+            rust_crates: Vec::new(),
+            go_module: None,
+        }
+    }
+
+    #[test]
+    fn relative_from_import_climbs_one_package_per_extra_dot() {
+        let file = Path::new("pkg/sub/mod.py");
+        let text = "from ..x import y\n";
+        let submodule = project(&["pkg/sub/mod.py", "pkg/x/__init__.py", "pkg/x/y.py"]);
+        assert_eq!(
+            imports(file, text, &submodule),
+            [PathBuf::from("pkg/x/y.py")]
+        );
+        let attribute = project(&["pkg/sub/mod.py", "pkg/x.py"]);
+        assert_eq!(imports(file, text, &attribute), [PathBuf::from("pkg/x.py")]);
+    }
+
+    #[test]
+    fn bare_dots_name_the_package() {
+        let p = project(&["pkg/__init__.py", "pkg/a.py", "pkg/b.py"]);
+        assert_eq!(
+            imports(Path::new("pkg/a.py"), "from . import b\n", &p),
+            [PathBuf::from("pkg/b.py")]
+        );
+    }
+}
@@ -0,0 +1,189 @@
+use super::{dir_of, regex, strip_comments, Project};
+use regex::Regex;
+use std::{
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+/// Module files reached through `mod` declarations, `use` statements and
+/// inline `crate::`/`self::`/`super::` paths.
+pub fn imports(file: &Path, text: &str, project: &Project) -> Vec<PathBuf> {
+    let Some((src, module)) = locate(file, project) else {
+        return Vec::new();
+    };
+    let here = Here {
+        src: &src,
+        module: &module,
+        project,
+    };
+    let text = strip_comments(text, &['"']);
+    let mut out = Vec::new();
+    for child in mod_decls(&text) {
+        let segs: Vec<&str> = module.iter().map(String::as_str).chain([child]).collect();
+        out.extend(here.module_file(&src, &segs, segs.len()));
+    }
+    for path in use_paths(&text) {
+        out.extend(here.resolve(&path, true));
+    }
+    for path in inline_paths(&text) {
+        out.extend(here.resolve(path, false));
+    }
+    out
+}
+
+/// Names in `mod name;` declarations (inline `mod name { .. }` has no file).
+fn mod_decls(text: &str) -> Vec<&str> {
+    static RE: OnceLock<Option<Regex>> = OnceLock::new();
+    regex(&RE, r"(?m)^\s*(?:pub(?:\([^)]*\))?\s+)?mod\s+(\w+)\s*;").map_or_else(Vec::new, |re| {
+        re.captures_iter(text)
+            .filter_map(|c| c.get(1).map(|m| m.as_str()))
+            .collect()
+    })
+}
+
+/// Every path named by `use` statements, with groups flattened.
+fn use_paths(text: &str) -> Vec<String> {
+    static RE: OnceLock<Option<Regex>> = OnceLock::new();
+    let mut paths = Vec::new();
+    if let Some(re) = regex(&RE, r"\buse\s+([^;]+);") {
+        for cap in re.captures_iter(text) {
+            expand("", cap.get(1).map_or("", |m| m.as_str()), &mut paths);
+        }
+    }
+    paths
+}
+
+/// `a::b::c` paths written in code, such as `crate::scanner::scan(..)`.
+fn inline_paths(text: &str) -> Vec<&str> {
+    static RE: OnceLock<Option<Regex>> = OnceLock::new();
+    regex(&RE, r"\b[A-Za-z_]\w*(?:::[A-Za-z_]\w*)+").map_or_else(Vec::new, |re| {
+        re.find_iter(text).map(|m| m.as_str()).collect()
+    })
+}
+
+/// The file being read: its crate's source directory and its module path.
+struct Here<'a> {
+    src: &'a Path,
+    module: &'a [String],
+    project: &'a Project,
+}
+
+impl Here<'_> {
+    /// The module file a path like `crate::a::b::Item` lives in. Paths not
+    /// anchored at `crate`, `self`, `super` or a project crate are tried as
+    /// child modules, but only for `use` statements (`in_use`).
+    fn resolve(&self, path: &str, in_use: bool) -> Option<PathBuf> {
+        let segs: Vec<&str> = path.split("::").map(str::trim).collect();
+        let (first, rest) = segs.split_first()?;
+        let (src, base): (&Path, Vec<&str>) = match *first {
+            "crate" => (self.src, Vec::new()),
+            "self" => (self.src, self.module.iter().map(String::as_str).collect()),
+            "super" => {
+                let ups = 1 + rest.iter().take_while(|s| **s == "super").count();
+                let keep = self.module.len().checked_sub(ups)?;
+                let base = self.module.iter().take(keep).map(String::as_str).collect();
+                return self.under(self.src, base, rest.get(ups - 1..)?);
+            }
+            name => {
+                if let Some(krate) = self.project.rust_crates.iter().find(|c| c.name == name) {
+                    (krate.src.as_path(), Vec::new())
+                } else if in_use {
+                    return self.under(
+                        self.src,
+                        self.module.iter().map(String::as_str).collect(),
+                        &segs,
+                    );
+                } else {
+                    return None;
+                }
+            }
+        };
+        self.under(src, base, rest)
+    }
+
+    /// The deepest module file for `base` + a prefix of `rest`, needing at
+    /// least one segment of `rest`.
+    fn under(&self, src: &Path, base: Vec<&str>, rest: &[&str]) -> Option<PathBuf> {
+        let min = base.len() + 1;
+        let mut segs = base;
+        segs.extend(rest.iter().copied());
+        (min..=segs.len())
+            .rev()
+            .find_map(|n| self.module_file(src, &segs, n))
+    }
+
+    /// `src/a/b.rs` or `src/a/b/mod.rs` for the first `n` segments.
+    fn module_file(&self, src: &Path, segs: &[&str], n: usize) -> Option<PathBuf> {
+        let dir = segs
+            .get(..n)?
+            .iter()
+            .fold(src.to_path_buf(), |p, s| p.join(s));
+        self.project
+            .first([dir.with_extension("rs"), dir.join("mod.rs")])
+    }
+}
+
+/// Crate source directory above `file` (the nearest folder holding a
+/// `lib.rs` or `main.rs`) and the module path of `file` inside it.
+fn locate(file: &Path, project: &Project) -> Option<(PathBuf, Vec<String>)> {
+    let src = dir_of(file)
+        .ancestors()
+        .find(|d| project.has(&d.join("lib.rs")) || project.has(&d.join("main.rs")))?;
+    let rel = file.strip_prefix(src).ok()?.with_extension("");
+    let mut module: Vec<String> = rel
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect();
+    if module.len() == 1 && matches!(module.first().map(String::as_str), Some("lib" | "main")) {
+        module.clear();
+    }
+    if module.last().is_some_and(|m| m == "mod") {
+        module.pop();
+    }
+    Some((src.to_path_buf(), module))
+}
+
+/// Flatten a use tree: `a::{b, c::{d, e as f}, self}` gives `a::b`, `a::c::d`,
+/// `a::c::e` and `a`.
+fn expand(prefix: &str, tree: &str, out: &mut Vec<String>) {
+    for item in split_top(tree) {
+        let item = item.trim();
+        if let Some(open) = item.find('{') {
+            let inner = item.get(open + 1..item.rfind('}').unwrap_or(item.len()));
+            expand(
+                &format!("{prefix}{}", item.get(..open).unwrap_or("")),
+                inner.unwrap_or(""),
+                out,
+            );
+            continue;
+        }
+        let name = item.split(" as ").next().unwrap_or(item).trim();
+        let path = format!("{prefix}{name}");
+        let path = path.trim_end_matches("::*").trim_end_matches("::self");
+        if !path.is_empty() && path != "*" {
+            out.push(path.to_string());
+        }
+    }
+}
+
+/// Split on commas outside braces.
+fn split_top(tree: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let (mut depth, mut start) = (0usize, 0);
+    for (i, c) in tree.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parts.extend(tree.get(start..i));
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.extend(tree.get(start..));
+    parts
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,68 @@
+use super::*;
+
+fn project(files: &[&str]) -> Project {
+    Project {
+        files: files.iter().map(PathBuf::from).collect(),
+        rust_crates: Vec::new(),
+        go_module: None,
+    }
+}
+
+fn resolve(project: &Project, file: &str, path: &str) -> Option<PathBuf> {
+    let (src, module) = locate(Path::new(file), project)?;
+    let here = Here {
+        src: &src,
+        module: &module,
+        project,
+    };
+    here.resolve(path, true)
+}
+
+#[test]
+fn expand_flattens_use_groups() {
+    let mut out = Vec::new();
+    expand("", "a::{b, c::{d, e as f}, self}", &mut out);
+    assert_eq!(out, ["a::b", "a::c::d", "a::c::e", "a"]);
+}
+
+#[test]
+fn split_top_ignores_nested_commas() {
+    assert_eq!(split_top("a, b::{c, d}, e"), ["a", " b::{c, d}", " e"]);
+}
+
+#[test]
+fn resolves_crate_self_and_super_paths() {
+    let p = project(&[
+        "src/main.rs",
+        "src/a/mod.rs",
+        "src/a/b.rs",
+        "src/a/x.rs",
+        "src/a/b/c.rs",
+        "src/a/b/c/d.rs",
+        "src/top.rs",
+    ]);
+    let file = "src/a/b/c.rs";
+    let found = |path: &str| resolve(&p, file, path);
+    assert_eq!(found("crate::a::b::Item"), Some("src/a/b.rs".into()));
+    assert_eq!(found("self::d::f"), Some("src/a/b/c/d.rs".into()));
+    assert_eq!(found("super::super::x"), Some("src/a/x.rs".into()));
+    assert_eq!(found("super::super::super::top"), Some("src/top.rs".into()));
+    assert_eq!(found("super::super::super::super::top"), None);
+}
+
+#[test]
+fn use_groups_reach_every_module() {
+    let p = project(&["src/lib.rs", "src/a/mod.rs", "src/a/b.rs", "src/a/x.rs"]);
+    let text = "use crate::a::{b::Thing, x, self};\n// use crate::gone;\n";
+    let mut found = imports(Path::new("src/lib.rs"), text, &p);
+    found.sort();
+    found.dedup();
+    assert_eq!(
+        found,
+        [
+            PathBuf::from("src/a/b.rs"),
+            "src/a/mod.rs".into(),
+            "src/a/x.rs".into()
+        ]
+    );
+}
@@ -0,0 +1,215 @@
+use crate::{
+    analyzer,
+    bpe::Bpe,
+    categories, config,
+    diff::DiffOptions,
+    extensions, git,
+    history::History,
+    path_list::PathList,
+    scanner,
+    semmap::{self, SemmapData},
+    snapshot::Snapshot,
+    subsystems,
+    tokens::TokenCounter,
+    tui::panels::preview::Preview,
+    types::{AppState, Focus, GitSet, GitignoreFilter},
+};
+use anyhow::Result;
+use ratatui::{layout::Rect, widgets::ListState};
+use std::path::{Path, PathBuf};
+
+/// Scan `root` and build the initial selection shared by the TUI and headless mode.
+pub fn load(root: &Path) -> Result<AppState> {
+    let mut state = open(root)?;
+    if let Some(pending) = state.analysis.take() {
+        let wanted = pending.wanted.clone();
+        install_graph(&mut state, pending.wait(), wanted);
+    }
+    Ok(state)
+}
+
+/// Like [`load`], but without a SEMMAP the import graph is still being read
+/// on a thread when this returns; [`poll_analysis`] picks it up.
+pub fn open(root: &Path) -> Result<AppState> {
+    let root = root.canonicalize()?;
+
+    let config = config::load(&root)?;
+    let scan = scanner::scan(&root, &config)?;
+    let maps = semmap::sources::read(&root, &config, &scanner::all_files(&scan.tree))?;
+    let semmap_data = semmap::parse_all(&maps);
+    let has_semmap = semmap_data.is_some();
+    let semmap_issues = semmap::check::check(&maps, &scan, &config);
+    let status = match semmap_issues.len() {
+        0 => String::from("ready"),
+        n => format!("SEMMAP has {n} problem(s); run `pakr semmap check` for the list"),
+    };
+
+    let (mut cats, graph, analysis) = if let Some(sd) = semmap_data {
+        (
+            categories::from_semmap(&sd, &scan.tree, &config),
+            Some(sd),
+            None,
+        )
+    } else {
+        // Without a SEMMAP the import graph is read from the sources instead.
+        let sources: Vec<PathBuf> = scanner::all_files(&scan.tree)
+            .into_iter()
+            .filter(|p| !scan.ignored.contains(p))
+            .collect();
+        (
+            categories::from_heuristics(&scan.tree, &config),
+            None,
+            Some(analyzer::Pending::spawn(root.clone(), sources)),
+        )
+    };
+    let subs = graph.as_ref().map_or_else(Vec::new, subsystems::build);
+
+    // Not being in a repository (or an unreadable one) just means no git rows.
+    if let Ok(Some(changes)) = git::status(&root) {
+        cats.extend(categories::from_git(changes, &scan.file_sizes));
+    }
+    categories::apply_defaults(&mut cats, &config);
+
+    let exts = extensions::from_tree(&scan.tree);
+
+    let gitignore_filter = GitignoreFilter::new(scan.ignored);
+    let has_gitignore = gitignore_filter.has_filter();
+    let tokens = match &config.tokenizer {
+        Some(path) => TokenCounter::new(Box::new(Bpe::from_file(path)?)),
+        None => TokenCounter::default(),
+    };
+
+    let mut state = AppState {
+        root,
+        tree: scan.tree,
+        file_sizes: scan.file_sizes,
+        tokens,
+        categories: cats,
+        subsystems: subs,
+        extensions: exts,
+        exclude: PathList::default(),
+        include: PathList::default(),
+        manual_includes: Vec::new(),
+        has_semmap,
+        semmap_issues,
+        graph,
+        analysis,
+        dep_depth: None,
+        tag_filter: None,
+        has_gitignore,
+        respect_gitignore: has_gitignore,
+        gitignore_filter,
+        format: config.export_format()?.unwrap_or_default(),
+        budget: config.budget_tokens()?,
+        part_limit: None,
+        outline: false,
+        full_files: Vec::new(),
+        describe: false,
+        diff: DiffOptions::default(),
+        pending_parts: Vec::new(),
+        next_part: 0,
+        all_collapsed: false,
+        focus: Focus::Categories,
+        cat_cursor: 0,
+        sub_cursor: 0,
+        exclude_cursor: 0,
+        hover_path: None,
+        last_click: None,
+        history: History::default(),
+        finder: None,
+        profiles: None,
+        search: None,
+        preview: Preview::default(),
+        defaults: Snapshot::default(),
+        cat_list_state: ListState::default(),
+        sub_list_state: ListState::default(),
+        tree_list_state: ListState::default(),
+        cat_area: Rect::default(),
+        sub_area: Rect::default(),
+        tree_area: Rect::default(),
+        exclude_area: Rect::default(),
+        status,
+    };
+    state.defaults = Snapshot::capture(&state);
+    Ok(state)
+}
+
+/// Install the import graph if its thread has finished; `true` when it did.
+pub fn poll_analysis(state: &mut AppState) -> bool {
+    let Some(graph) = state
+        .analysis
+        .as_ref()
+        .and_then(analyzer::Pending::try_take)
+    else {
+        return false;
+    };
+    let wanted = state.analysis.take().and_then(|p| p.wanted);
+    install_graph(state, graph, wanted);
+    true
+}
+
+/// Build subsystems from a graph that arrived after the initial load. All
+/// start enabled, unless a session or profile applied meanwhile chose some.
+fn install_graph(state: &mut AppState, graph: Option<SemmapData>, wanted: Option<Vec<String>>) {
+    let mut subs = graph.as_ref().map_or_else(Vec::new, |g| {
+        subsystems::build_with_rest(g, state.file_sizes.keys())
+    });
+    state.defaults.subsystems = Some(subs.iter().map(|s| s.name.clone()).collect());
+    if let Some(wanted) = wanted {
+        for sub in &mut subs {
+            sub.enabled = wanted.contains(&sub.name);
+        }
+    }
+    state.subsystems = subs;
+    state.graph = graph;
+    state.clamp_cursors();
+}
+
+/// Add a "Git: since <rev>" category for files changed since `rev`.
+pub fn track_since(state: &mut AppState, rev: &str) -> Result<()> {
+    let files = git::since(&state.root, rev)?;
+    let cat = categories::git_since(rev, files, &state.file_sizes);
+    state.categories.push(cat);
+    Ok(())
+}
+
+/// Start from the git categories instead of the default ones: working-tree
+/// changes, or with `since` everything touched since that revision.
+pub fn select_changed(state: &mut AppState, since: bool) {
+    let sets: &[GitSet] = if since {
+        &[GitSet::Since, GitSet::Untracked]
+    } else {
+        &[GitSet::Modified, GitSet::Staged, GitSet::Untracked]
+    };
+    categories::enable_git(&mut state.categories, sets);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn files_without_imports_stay_selected_without_a_semmap() -> Result<()> {
+        let root = std::env::temp_dir().join(format!("pakr-app-{}", std::process::id()));
+        let files = [
+            ("Cargo.toml", "[package]\nname = \"demo\"\n"),
+            ("src/main.rs", "mod a;\nfn main() { a::run(); }\n"),
+            ("src/a.rs", "pub fn run() {}\n"),
+            ("src/orphan.rs", "pub fn unused() {}\n"),
+            ("tool.py", "print('hi')\n"),
+        ];
+        for (path, text) in files {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap_or(&root))?;
+            fs::write(path, text)?;
+        }
+        let state = load(&root);
+        fs::remove_dir_all(&root)?;
+        let selected = state?.selected_paths();
+        for path in ["src/main.rs", "src/a.rs", "src/orphan.rs", "tool.py"] {
+            assert!(selected.contains(&PathBuf::from(path)), "{path} dropped");
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,106 @@
+use crate::tokens::Tokenizer;
+use anyhow::{bail, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use regex::Regex;
+use std::{collections::HashMap, fs, path::Path};
+
+/// cl100k's pre-tokenizer minus the `\s+(?!\S)` look-ahead, which the
+/// `regex` crate cannot express; trailing whitespace runs merge slightly
+/// differently, everything else splits the same way.
+const CL100K: &str = r"(?i:'s|'t|'re|'ve|'m|'ll|'d)|[^\r\n\p{L}\p{N}]?\p{L}+|\p{N}{1,3}| ?[^\s\p{L}\p{N}]+[\r\n]*|\s*[\r\n]+|\s+";
+
+/// o200k's pre-tokenizer, with the same look-ahead dropped. It splits
+/// camel case and keeps contractions attached to their word.
+const O200K: &str = r"[^\r\n\p{L}\p{N}]?[\p{Lu}\p{Lt}\p{Lm}\p{Lo}\p{M}]*[\p{Ll}\p{Lm}\p{Lo}\p{M}]+(?i:'s|'t|'re|'ve|'m|'ll|'d)?|[^\r\n\p{L}\p{N}]?[\p{Lu}\p{Lt}\p{Lm}\p{Lo}\p{M}]+[\p{Ll}\p{Lm}\p{Lo}\p{M}]*(?i:'s|'t|'re|'ve|'m|'ll|'d)?|\p{N}{1,3}| ?[^\s\p{L}\p{N}]+[\r\n/]*|\s*[\r\n]+|\s+";
+
+/// Vocabularies at least this large are o200k-style (about 200k ranks,
+/// against cl100k's 100k) and get its pre-tokenizer.
+const O200K_MIN_RANKS: usize = 150_000;
+
+/// Byte-level BPE driven by a tiktoken rank file (`<base64 token> <rank>` per
+/// line, as shipped for `cl100k_base` and `o200k_base`).
+pub struct Bpe {
+    name: String,
+    ranks: HashMap<Vec<u8>, u32>,
+    splitter: Regex,
+}
+
+impl Bpe {
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("reading vocab {}", path.display()))?;
+        let mut ranks = HashMap::new();
+        for (n, line) in text
+            .lines()
+            .enumerate()
+            .filter(|(_, l)| !l.trim().is_empty())
+        {
+            let (token, rank) = line.split_once(' ').with_context(|| {
+                format!("{}:{}: expected `<token> <rank>`", path.display(), n + 1)
+            })?;
+            let bytes = STANDARD
+                .decode(token)
+                .with_context(|| format!("{}:{}: bad base64", path.display(), n + 1))?;
+            let rank: u32 = rank
+                .trim()
+                .parse()
+                .with_context(|| format!("{}:{}: bad rank", path.display(), n + 1))?;
+            ranks.insert(bytes, rank);
+        }
+        if ranks.is_empty() {
+            bail!("{} contains no tokens", path.display());
+        }
+        let name = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("bpe")
+            .to_string();
+        let pattern = if ranks.len() >= O200K_MIN_RANKS {
+            O200K
+        } else {
+            CL100K
+        };
+        let splitter = Regex::new(pattern)?;
+        Ok(Self {
+            name,
+            ranks,
+            splitter,
+        })
+    }
+
+    fn count_piece(&self, piece: &[u8]) -> usize {
+        if piece.len() <= 1 || self.ranks.contains_key(piece) {
+            return 1;
+        }
+        // Start from single bytes and repeatedly merge the lowest-ranked pair.
+        let mut bounds: Vec<usize> = (0..=piece.len()).collect();
+        loop {
+            let best = bounds
+                .windows(3)
+                .enumerate()
+                .filter_map(|(i, w)| {
+                    let (&start, &end) = (w.first()?, w.get(2)?);
+                    self.ranks.get(piece.get(start..end)?).map(|&r| (r, i + 1))
+                })
+                .min();
+            match best {
+                Some((_, mid)) => {
+                    bounds.remove(mid);
+                }
+                None => return bounds.len() - 1,
+            }
+        }
+    }
+}
+
+impl Tokenizer for Bpe {
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn count(&self, text: &str) -> usize {
+        self.splitter
+            .find_iter(text)
+            .map(|m| self.count_piece(m.as_str().as_bytes()))
+            .sum()
+    }
+}
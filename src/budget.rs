@@ -0,0 +1,102 @@
+use crate::types::{AppState, CategoryKind};
+use anyhow::{bail, Context, Result};
+use std::{collections::HashMap, path::PathBuf};
+
+/// Budgets offered by the TUI's `b` key, cycled in order after "off".
+pub const PRESETS: &[usize] = &[32_000, 100_000, 128_000, 200_000, 1_000_000];
+
+/// Parse `100k`, `1.5m`, `128000` or `200_000` into a token count.
+pub fn parse(spec: &str) -> Result<usize> {
+    let s = spec.trim().to_ascii_lowercase().replace('_', "");
+    let (num, mult) = if let Some(n) = s.strip_suffix('k') {
+        (n, 1_000.0)
+    } else if let Some(n) = s.strip_suffix('m') {
+        (n, 1_000_000.0)
+    } else {
+        (s.as_str(), 1.0)
+    };
+    let value: f64 = num
+        .parse()
+        .with_context(|| format!("bad token budget `{spec}`"))?;
+    if !value.is_finite() || value <= 0.0 {
+        bail!("token budget must be positive: `{spec}`");
+    }
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    Ok((value * mult).round() as usize)
+}
+
+/// Next budget after `current` in [`PRESETS`], wrapping back to none.
+pub fn next_preset(current: Option<usize>) -> Option<usize> {
+    match current {
+        None => PRESETS.first().copied(),
+        Some(b) => PRESETS.iter().copied().find(|&p| p > b),
+    }
+}
+
+pub fn fmt_tokens(tokens: usize) -> String {
+    if tokens >= 1_000_000 && tokens.is_multiple_of(100_000) {
+        format!("{}.{}m", tokens / 1_000_000, tokens % 1_000_000 / 100_000)
+    } else if tokens >= 1000 {
+        format!("{}k", tokens / 1000)
+    } else {
+        tokens.to_string()
+    }
+}
+
+/// Exclude files until the selection fits `state.budget`, returning what was
+/// dropped in the order it went.
+pub fn auto_fit(state: &mut AppState) -> Vec<PathBuf> {
+    let Some(budget) = state.budget else {
+        return Vec::new();
+    };
+    let mut total = state.total_tokens();
+    let tiers = category_tiers(state);
+    let selected = state.selected_paths();
+    let top = selected
+        .iter()
+        .filter_map(|p| tiers.get(p).map(|t| t.0))
+        .max()
+        .unwrap_or(0);
+    let mut candidates: Vec<(DropKey, PathBuf)> = selected
+        .into_iter()
+        .map(|p| {
+            let (layer, docs) = tiers.get(&p).copied().unwrap_or_default();
+            let tokens = state.file_tokens(&p).unwrap_or(0);
+            ((top > 0 && layer == top, docs, layer, tokens), p)
+        })
+        .collect();
+    candidates.sort_by_key(|c| std::cmp::Reverse(c.0));
+
+    let mut dropped = Vec::new();
+    for (_, path) in candidates {
+        if total <= budget {
+            break;
+        }
+        total = total.saturating_sub(state.file_tokens(&path).unwrap_or(0));
+        state.exclude.insert(path.clone());
+        dropped.push(path);
+    }
+    dropped
+}
+
+/// Larger keys are dropped first: the highest SEMMAP layer in the
+/// selection, then Assets/Docs, then the rest, deeper layers and bigger
+/// files first.
+type DropKey = (bool, bool, u16, usize);
+
+fn category_tiers(state: &AppState) -> HashMap<PathBuf, (u16, bool)> {
+    let mut tiers: HashMap<PathBuf, (u16, bool)> = HashMap::new();
+    for cat in &state.categories {
+        for file in &cat.files {
+            let tier = tiers.entry(file.clone()).or_default();
+            match cat.kind {
+                CategoryKind::SemmapLayer { index, .. } => {
+                    tier.0 = tier.0.max(u16::from(index) + 1);
+                }
+                CategoryKind::Assets | CategoryKind::Docs => tier.1 = true,
+                _ => {}
+            }
+        }
+    }
+    tiers
+}
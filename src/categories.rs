@@ -1,14 +1,21 @@
 use crate::{
+    config::{Bucket, Config},
+    git::Changes,
     scanner,
     semmap::SemmapData,
-    types::{Category, CategoryKind},
+    types::{Category, CategoryKind, GitSet},
 };
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
+    hash::BuildHasher,
     path::{Path, PathBuf},
 };
 
-pub fn from_semmap(data: &SemmapData, tree: &crate::types::FileNode) -> Vec<Category> {
+pub fn from_semmap(
+    data: &SemmapData,
+    tree: &crate::types::FileNode,
+    config: &Config,
+) -> Vec<Category> {
     let mut cats: Vec<Category> = data
         .layers
         .iter()
@@ -32,8 +39,9 @@ pub fn from_semmap(data: &SemmapData, tree: &crate::types::FileNode) -> Vec<Cate
 
     if !untracked.is_empty() {
         // Split untracked into docs vs other
-        let docs: Vec<PathBuf> = untracked.iter().filter(|p| is_doc(p)).cloned().collect();
-        let other: Vec<PathBuf> = untracked.into_iter().filter(|p| !is_doc(p)).collect();
+        let (docs, other): (Vec<PathBuf>, Vec<PathBuf>) = untracked
+            .into_iter()
+            .partition(|p| in_bucket(p, Bucket::Docs, config));
 
         if !docs.is_empty() {
             cats.push(Category {
@@ -58,48 +66,130 @@ fn is_layer_on_by_default(index: u8) -> bool {
     index <= 2
 }
 
-pub fn from_heuristics(tree: &crate::types::FileNode) -> Vec<Category> {
+pub fn from_heuristics(tree: &crate::types::FileNode, config: &Config) -> Vec<Category> {
     let all = scanner::all_files(tree);
-
-    let source: Vec<PathBuf> = all.iter().filter(|p| is_source(p)).cloned().collect();
-    let config: Vec<PathBuf> = all.iter().filter(|p| is_config(p)).cloned().collect();
-    let docs: Vec<PathBuf> = all.iter().filter(|p| is_doc(p)).cloned().collect();
-    let build: Vec<PathBuf> = all.iter().filter(|p| is_build(p)).cloned().collect();
-    let assets: Vec<PathBuf> = all
-        .iter()
-        .filter(|p| !is_source(p) && !is_config(p) && !is_doc(p) && !is_build(p))
-        .cloned()
-        .collect();
+    let bucket = |b: Bucket| -> Vec<PathBuf> {
+        all.iter()
+            .filter(|p| in_bucket(p, b, config))
+            .cloned()
+            .collect()
+    };
 
     vec![
         Category {
             kind: CategoryKind::Source,
-            files: source,
+            files: bucket(Bucket::Source),
             enabled: true,
         },
         Category {
             kind: CategoryKind::Config,
-            files: config,
+            files: bucket(Bucket::Config),
             enabled: true,
         },
         Category {
             kind: CategoryKind::Docs,
-            files: docs,
+            files: bucket(Bucket::Docs),
             enabled: false,
         },
         Category {
             kind: CategoryKind::Build,
-            files: build,
+            files: bucket(Bucket::Build),
             enabled: false,
         },
         Category {
             kind: CategoryKind::Assets,
-            files: assets,
+            files: bucket(Bucket::Assets),
             enabled: false,
         },
     ]
 }
 
+/// Override the built-in on/off defaults with the configured `categories`
+/// (by name) and `layers` (SEMMAP layer indices).
+pub fn apply_defaults(cats: &mut [Category], config: &Config) {
+    for cat in cats {
+        match (&cat.kind, &config.layers, &config.categories) {
+            (CategoryKind::SemmapLayer { index, .. }, Some(layers), _) => {
+                cat.enabled = layers.contains(index);
+            }
+            (CategoryKind::SemmapLayer { .. }, None, _) | (_, _, None) => {}
+            (_, _, Some(names)) => {
+                cat.enabled = names.iter().any(|n| n.eq_ignore_ascii_case(cat.name()));
+            }
+        }
+    }
+}
+
+/// A configured extension rule puts a file in exactly that bucket; other
+/// files go by the built-in lists (a file can land in more than one).
+pub fn in_bucket(p: &Path, bucket: Bucket, config: &Config) -> bool {
+    if let Some(rule) = config.bucket(p) {
+        return rule == bucket;
+    }
+    match bucket {
+        Bucket::Source => is_source(p),
+        Bucket::Config => is_config(p),
+        Bucket::Docs => is_doc(p),
+        Bucket::Build => is_build(p),
+        Bucket::Assets => !is_source(p) && !is_config(p) && !is_doc(p) && !is_build(p),
+    }
+}
+
+/// One disabled category per `git status` bucket. Paths the scanner never
+/// saw (deleted files, ignored folders) are left out.
+pub fn from_git<S: BuildHasher>(
+    changes: Changes,
+    scanned: &HashMap<PathBuf, u64, S>,
+) -> Vec<Category> {
+    [
+        (GitSet::Modified, changes.modified),
+        (GitSet::Staged, changes.staged),
+        (GitSet::Untracked, changes.untracked),
+    ]
+    .into_iter()
+    .map(|(set, files)| git_category(set, git_label(set, ""), files, scanned))
+    .collect()
+}
+
+pub fn git_since<S: BuildHasher>(
+    rev: &str,
+    files: Vec<PathBuf>,
+    scanned: &HashMap<PathBuf, u64, S>,
+) -> Category {
+    git_category(GitSet::Since, git_label(GitSet::Since, rev), files, scanned)
+}
+
+/// Enable exactly the git categories in `sets` and switch every other
+/// category off, so the selection starts from what changed.
+pub fn enable_git(cats: &mut [Category], sets: &[GitSet]) {
+    for cat in cats {
+        cat.enabled = matches!(&cat.kind, CategoryKind::Git { set, .. } if sets.contains(set));
+    }
+}
+
+fn git_category<S: BuildHasher>(
+    set: GitSet,
+    label: String,
+    mut files: Vec<PathBuf>,
+    scanned: &HashMap<PathBuf, u64, S>,
+) -> Category {
+    files.retain(|p| scanned.contains_key(p));
+    Category {
+        kind: CategoryKind::Git { set, label },
+        files,
+        enabled: false,
+    }
+}
+
+fn git_label(set: GitSet, rev: &str) -> String {
+    match set {
+        GitSet::Modified => "Git: modified".to_string(),
+        GitSet::Staged => "Git: staged".to_string(),
+        GitSet::Untracked => "Git: untracked".to_string(),
+        GitSet::Since => format!("Git: since {rev}"),
+    }
+}
+
 fn ext(p: &Path) -> &str {
     p.extension().and_then(|e| e.to_str()).unwrap_or("")
 }
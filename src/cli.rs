@@ -0,0 +1,135 @@
+use clap::{Parser, Subcommand};
+use pakr::{budget, format::ExportFormat};
+use std::path::PathBuf;
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "pakr",
+    about = "Terminal file packer for AI context",
+    args_conflicts_with_subcommands = true
+)]
+pub struct Args {
+    #[arg(default_value = ".")]
+    pub path: PathBuf,
+    /// Ignore the last session and start from the defaults
+    #[arg(long)]
+    pub fresh: bool,
+    #[command(flatten)]
+    pub common: CommonArgs,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// Options shared by the TUI and `pakr pack`.
+#[derive(clap::Args, Debug)]
+pub struct CommonArgs {
+    /// Export format: plain, markdown, xml or json [default: plain]
+    #[arg(long)]
+    pub format: Option<ExportFormat>,
+    /// tiktoken rank file (cl100k or o200k base) for exact token counts
+    #[arg(long, value_name = "FILE")]
+    pub tokenizer: Option<PathBuf>,
+    /// Token budget, e.g. `100k` or `1m`
+    #[arg(long, value_parser = budget::parse)]
+    pub budget: Option<usize>,
+    /// Split exports into parts of at most this many tokens, e.g. `50k`
+    #[arg(long, value_parser = budget::parse, value_name = "TOKENS")]
+    pub split: Option<usize>,
+    /// Export signatures only (Rust, JS/TS, Python); see `pack --full`
+    #[arg(long)]
+    pub outline: bool,
+    /// Write each file's SEMMAP description above it in the export
+    #[arg(long)]
+    pub describe: bool,
+    /// Keep only files tagged `[TAG]` in SEMMAP.md
+    #[arg(long, value_name = "TAG")]
+    pub tag: Option<String>,
+    /// Start from a profile saved under .pakr/profiles
+    #[arg(long, value_name = "NAME")]
+    pub profile: Option<String>,
+    /// Start from modified, staged and untracked files
+    #[arg(long)]
+    pub changed: bool,
+    /// Start from files changed since this git revision, plus untracked ones
+    #[arg(long, value_name = "REF")]
+    pub since: Option<String>,
+    /// Append a unified diff of the selected files against --diff-base
+    #[arg(long, conflicts_with = "diff_only")]
+    pub diff: bool,
+    /// Export only the diff, without full file contents
+    #[arg(long)]
+    pub diff_only: bool,
+    /// Revision to diff against [default: the --since ref, else HEAD]
+    #[arg(long, value_name = "REF")]
+    pub diff_base: Option<String>,
+    /// Lines of context around each diff hunk
+    #[arg(short = 'U', long, value_name = "N", default_value_t = 3)]
+    pub unified: usize,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Pack the selection without opening the TUI
+    Pack(Box<PackArgs>),
+    /// Create or check SEMMAP.md
+    Semmap {
+        #[command(subcommand)]
+        command: SemmapCommand,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum SemmapCommand {
+    /// Write SEMMAP.md from the project's imports, keeping existing descriptions
+    Init(SemmapArgs),
+    /// Report SEMMAP.md entries that no longer match the tree; fails if any
+    Check {
+        #[arg(default_value = ".")]
+        path: PathBuf,
+    },
+}
+
+#[derive(clap::Args, Debug)]
+pub struct SemmapArgs {
+    #[arg(default_value = ".")]
+    pub path: PathBuf,
+    /// Print to stdout instead of writing SEMMAP.md
+    #[arg(long, conflicts_with = "output")]
+    pub stdout: bool,
+    /// Write here instead of over the existing map file
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(clap::Args, Debug)]
+#[command(group(clap::ArgGroup::new("dest").required(true).args(["output", "stdout"])))]
+pub struct PackArgs {
+    #[arg(default_value = ".")]
+    pub path: PathBuf,
+    /// Glob of files to include (repeatable); replaces the default categories
+    #[arg(short, long)]
+    pub include: Vec<String>,
+    /// Glob of files to exclude (repeatable)
+    #[arg(short = 'x', long)]
+    pub exclude: Vec<String>,
+    /// With --outline, keep matching files in full (repeatable)
+    #[arg(long, value_name = "GLOB")]
+    pub full: Vec<String>,
+    /// SEMMAP layers to enable, e.g. `0-2` or `0,3`
+    #[arg(long)]
+    pub layer: Option<String>,
+    /// Keep gitignored files in the selection
+    #[arg(long)]
+    pub no_gitignore: bool,
+    #[command(flatten)]
+    pub common: CommonArgs,
+    /// Drop files in priority order until the selection fits --budget
+    #[arg(long, requires = "budget")]
+    pub fit: bool,
+    /// Write the export to this file
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+    /// Print the export to stdout
+    #[arg(long)]
+    pub stdout: bool,
+}
@@ -0,0 +1,155 @@
+use crate::{budget, format::ExportFormat};
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use std::{
+    collections::HashMap,
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+/// Project settings file, read from the scanned root.
+pub const PROJECT_FILE: &str = ".pakr.toml";
+/// Gitignore-syntax file of paths pakr never scans, at any depth.
+pub const IGNORE_FILE: &str = ".pakrignore";
+
+/// Folders skipped unless `ignore_dirs` says otherwise.
+pub const DEFAULT_IGNORE_DIRS: &[&str] = &[
+    ".git",
+    "node_modules",
+    "target",
+    "dist",
+    "build",
+    ".next",
+    ".nuxt",
+    "__pycache__",
+    "coverage",
+    ".cache",
+];
+
+/// Settings from `.pakr.toml` layered over the user config. Every field is
+/// optional; command-line flags win over both.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Folder names never scanned; replaces [`DEFAULT_IGNORE_DIRS`].
+    pub ignore_dirs: Option<Vec<String>>,
+    /// Category names (case-insensitive) enabled at startup; the rest start off.
+    pub categories: Option<Vec<String>>,
+    /// SEMMAP layers enabled at startup.
+    pub layers: Option<Vec<u8>>,
+    /// Extension (without the dot) to heuristic category, e.g. `vue = "source"`.
+    pub extensions: HashMap<String, Bucket>,
+    pub format: Option<String>,
+    /// Token budget: `128000` or `"128k"`.
+    pub budget: Option<BudgetValue>,
+    /// tiktoken rank file; relative paths resolve against the config file.
+    pub tokenizer: Option<PathBuf>,
+    /// SEMMAP files or globs, root-relative, read in order and joined.
+    pub semmap: Option<Vec<String>>,
+}
+
+/// The heuristic categories an extension can be assigned to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Bucket {
+    Source,
+    Config,
+    Docs,
+    Build,
+    Assets,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum BudgetValue {
+    Tokens(usize),
+    Text(String),
+}
+
+impl Config {
+    pub fn ignore_dirs(&self) -> Vec<String> {
+        self.ignore_dirs.clone().unwrap_or_else(|| {
+            DEFAULT_IGNORE_DIRS
+                .iter()
+                .map(ToString::to_string)
+                .collect()
+        })
+    }
+
+    /// Category override for `path`, if its extension has one.
+    pub fn bucket(&self, path: &Path) -> Option<Bucket> {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        self.extensions.get(&ext).copied()
+    }
+
+    pub fn export_format(&self) -> Result<Option<ExportFormat>> {
+        self.format
+            .as_deref()
+            .map(|f| f.parse().map_err(|e: String| anyhow!(e)))
+            .transpose()
+    }
+
+    pub fn budget_tokens(&self) -> Result<Option<usize>> {
+        match &self.budget {
+            None => Ok(None),
+            Some(BudgetValue::Tokens(n)) => Ok(Some(*n)),
+            Some(BudgetValue::Text(s)) => budget::parse(s).map(Some),
+        }
+    }
+
+    /// `over` wins field by field; extension rules are combined.
+    #[must_use]
+    fn merge(mut self, over: Self) -> Self {
+        self.extensions.extend(over.extensions);
+        Self {
+            ignore_dirs: over.ignore_dirs.or(self.ignore_dirs),
+            categories: over.categories.or(self.categories),
+            layers: over.layers.or(self.layers),
+            extensions: self.extensions,
+            format: over.format.or(self.format),
+            budget: over.budget.or(self.budget),
+            tokenizer: over.tokenizer.or(self.tokenizer),
+            semmap: over.semmap.or(self.semmap),
+        }
+    }
+}
+
+/// The user config merged with `root/.pakr.toml`; missing files are fine,
+/// malformed ones are errors.
+pub fn load(root: &Path) -> Result<Config> {
+    let user = match user_dir() {
+        Some(dir) => read(&dir.join("config.toml"))?,
+        None => Config::default(),
+    };
+    Ok(user.merge(read(&root.join(PROJECT_FILE))?))
+}
+
+/// `$XDG_CONFIG_HOME/pakr/ignore`, a user-wide `.pakrignore`.
+pub fn user_ignore_file() -> Option<PathBuf> {
+    user_dir()
+        .map(|dir| dir.join("ignore"))
+        .filter(|p| p.is_file())
+}
+
+fn user_dir() -> Option<PathBuf> {
+    let base = env::var_os("XDG_CONFIG_HOME")
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|h| PathBuf::from(h).join(".config")))?;
+    Some(base.join("pakr"))
+}
+
+fn read(path: &Path) -> Result<Config> {
+    if !path.is_file() {
+        return Ok(Config::default());
+    }
+    let text = fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    let mut config: Config =
+        toml::from_str(&text).with_context(|| format!("parsing {}", path.display()))?;
+    if let (Some(tok), Some(dir)) = (&mut config.tokenizer, path.parent()) {
+        if tok.is_relative() {
+            *tok = dir.join(&*tok);
+        }
+    }
+    Ok(config)
+}
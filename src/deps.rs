@@ -0,0 +1,56 @@
+use crate::semmap::SemmapData;
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+/// Which way to follow the dependency edges.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// What the files import, and what that imports in turn.
+    Dependencies,
+    /// Files importing them, and the files importing those.
+    Dependents,
+}
+
+/// Files reachable from `starts` within `depth` hops (unlimited when
+/// `None`), nearest first, not counting the starts themselves.
+pub fn closure(
+    graph: &SemmapData,
+    starts: &[PathBuf],
+    direction: Direction,
+    depth: Option<usize>,
+) -> Vec<PathBuf> {
+    let adj = match direction {
+        Direction::Dependencies => graph.adjacency(),
+        Direction::Dependents => reverse(graph),
+    };
+    let mut seen: HashSet<&Path> = starts.iter().map(PathBuf::as_path).collect();
+    let mut frontier: Vec<&Path> = starts.iter().map(PathBuf::as_path).collect();
+    let mut out = Vec::new();
+    let mut hops = 0;
+    while !frontier.is_empty() && depth.is_none_or(|d| hops < d) {
+        let mut next = Vec::new();
+        for file in frontier {
+            for to in adj.get(file).into_iter().flatten() {
+                if seen.insert(to) {
+                    out.push(to.clone());
+                    next.push(to.as_path());
+                }
+            }
+        }
+        frontier = next;
+        hops += 1;
+    }
+    out
+}
+
+fn reverse(graph: &SemmapData) -> HashMap<PathBuf, Vec<PathBuf>> {
+    let mut map: HashMap<PathBuf, Vec<PathBuf>> = HashMap::new();
+    for edge in &graph.edges {
+        map.entry(edge.to.clone())
+            .or_default()
+            .push(edge.from.clone());
+    }
+    map
+}
@@ -0,0 +1,211 @@
+use crate::{
+    format::Document,
+    git::{self, Revision},
+    types::AppState,
+};
+use anyhow::Result;
+use similar::TextDiff;
+use std::{
+    collections::HashSet,
+    fmt::Write as _,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DiffMode {
+    #[default]
+    Off,
+    /// Full file contents followed by the diff.
+    Append,
+    /// Only the diff; file contents are left out.
+    Only,
+}
+
+impl DiffMode {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Append => "append",
+            Self::Only => "only",
+        }
+    }
+
+    #[must_use]
+    pub fn next(self) -> Self {
+        match self {
+            Self::Off => Self::Append,
+            Self::Append => Self::Only,
+            Self::Only => Self::Off,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DiffOptions {
+    pub mode: DiffMode,
+    /// Revision the working tree is compared against.
+    pub base: String,
+    /// Unchanged lines kept around each hunk.
+    pub context: usize,
+}
+
+impl Default for DiffOptions {
+    fn default() -> Self {
+        Self {
+            mode: DiffMode::Off,
+            base: "HEAD".to_string(),
+            context: 3,
+        }
+    }
+}
+
+/// One `git diff <base>`-style document covering `paths`, or `None` when
+/// none of them changed. Failures (no repository, unknown revision) come
+/// back as an error document.
+pub fn document(state: &AppState, paths: &[PathBuf]) -> Option<Document> {
+    let path = format!("git diff {}", state.diff.base);
+    let content = match patch(state, paths) {
+        Ok(text) if text.is_empty() => return None,
+        Ok(text) => Ok(text),
+        Err(e) => Err(format!("{e:#}")),
+    };
+    let tokens = content
+        .as_deref()
+        .map_or(0, |text| state.tokens.count_text(text));
+    Some(Document {
+        path,
+        note: None,
+        content,
+        tokens,
+        lang: Some("diff"),
+        description: None,
+    })
+}
+
+/// Patches for the files changed since the base that belong to the
+/// selection: selected files, plus deleted files whose folder holds a
+/// selected file and that no exclusion covers.
+fn patch(state: &AppState, paths: &[PathBuf]) -> Result<String> {
+    let revision = Revision::open(&state.root, &state.diff.base)?;
+    let selected: HashSet<&PathBuf> = paths.iter().collect();
+    let folders: HashSet<&Path> = paths.iter().filter_map(|p| p.parent()).collect();
+    let in_selection = |rel: &PathBuf| {
+        selected.contains(rel)
+            || (!state.file_sizes.contains_key(rel)
+                && rel.parent().is_some_and(|d| folders.contains(d))
+                && !state.exclude.contains(rel))
+    };
+    let mut out = String::new();
+    for rel in git::since_with_untracked(&state.root, &state.diff.base)? {
+        if !in_selection(&rel) {
+            continue;
+        }
+        let old = revision.read(&rel)?;
+        let new = match fs::read(state.root.join(&rel)) {
+            Ok(bytes) => Some(bytes),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e.into()),
+        };
+        if old == new {
+            continue;
+        }
+        let name = rel.to_string_lossy().replace('\\', "/");
+        file_patch(
+            &mut out,
+            &name,
+            old.as_deref(),
+            new.as_deref(),
+            state.diff.context,
+        );
+    }
+    Ok(out)
+}
+
+fn file_patch(
+    out: &mut String,
+    name: &str,
+    old: Option<&[u8]>,
+    new: Option<&[u8]>,
+    context: usize,
+) {
+    let _ = writeln!(out, "diff --git a/{name} b/{name}");
+    match (old, new) {
+        (None, _) => {
+            let _ = writeln!(out, "new file mode 100644");
+        }
+        (_, None) => {
+            let _ = writeln!(out, "deleted file mode 100644");
+        }
+        _ => {}
+    }
+    let (Ok(old_text), Ok(new_text)) = (
+        std::str::from_utf8(old.unwrap_or_default()),
+        std::str::from_utf8(new.unwrap_or_default()),
+    ) else {
+        let _ = writeln!(out, "Binary files a/{name} and b/{name} differ");
+        return;
+    };
+    let side = |present: bool, prefix: &str| {
+        if present {
+            format!("{prefix}/{name}")
+        } else {
+            "/dev/null".to_string()
+        }
+    };
+    let diff = TextDiff::from_lines(old_text, new_text);
+    let _ = write!(
+        out,
+        "{}",
+        diff.unified_diff()
+            .context_radius(context)
+            .header(&side(old.is_some(), "a"), &side(new.is_some(), "b"))
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+
+    fn git(root: &Path, args: &[&str]) -> Result<()> {
+        let status = Command::new("git")
+            .args([
+                "-c",
+                "user.name=t",
+                "-c",
+                "user.email=t@t",
+                "-c",
+                "commit.gpgsign=false",
+            ])
+            .args(args)
+            .current_dir(root)
+            .status()?;
+        anyhow::ensure!(status.success(), "git {args:?} failed");
+        Ok(())
+    }
+
+    #[test]
+    fn deleted_and_new_files_get_headers() -> Result<()> {
+        let root = std::env::temp_dir().join(format!("pakr-diff-{}", std::process::id()));
+        fs::create_dir_all(root.join("src"))?;
+        fs::write(root.join("src/kept.rs"), "fn a() {}\n")?;
+        fs::write(root.join("src/gone.rs"), "fn b() {}\n")?;
+        git(&root, &["init", "-q"])?;
+        git(&root, &["add", "."])?;
+        git(&root, &["commit", "-q", "-m", "base"])?;
+        fs::remove_file(root.join("src/gone.rs"))?;
+        fs::write(root.join("src/new.rs"), "fn c() {}\n")?;
+        let state = crate::app::load(&root);
+        let text = state.and_then(|s| patch(&s, &s.selected_paths()));
+        fs::remove_dir_all(&root)?;
+        let text = text?;
+        assert!(text.contains("diff --git a/src/gone.rs b/src/gone.rs\ndeleted file mode 100644\n--- a/src/gone.rs\n+++ /dev/null\n"), "{text}");
+        assert!(
+            text.contains("new file mode 100644\n--- /dev/null\n+++ b/src/new.rs\n"),
+            "{text}"
+        );
+        assert!(!text.contains("kept.rs"), "{text}");
+        Ok(())
+    }
+}
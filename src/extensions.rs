@@ -0,0 +1,39 @@
+use crate::{
+    scanner,
+    types::{ExtMode, ExtensionGroup, FileNode},
+};
+use std::{collections::HashMap, path::PathBuf};
+
+/// Group every scanned file by extension, most common first.
+pub fn from_tree(tree: &FileNode) -> Vec<ExtensionGroup> {
+    let mut groups: HashMap<String, Vec<PathBuf>> = HashMap::new();
+    for path in scanner::all_files(tree) {
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+        groups.entry(ext).or_default().push(path);
+    }
+    let mut out: Vec<ExtensionGroup> = groups
+        .into_iter()
+        .map(|(ext, files)| ExtensionGroup {
+            ext,
+            files,
+            mode: ExtMode::Inherit,
+        })
+        .collect();
+    out.sort_by(|a, b| b.files.len().cmp(&a.files.len()).then(a.ext.cmp(&b.ext)));
+    out
+}
+
+/// Flip a group against what the user currently sees: a fully selected
+/// extension is forced out, anything else is forced in, and a forced
+/// group goes back to following the categories.
+pub fn next_mode(group: &ExtensionGroup, all_selected: bool) -> ExtMode {
+    match group.mode {
+        ExtMode::Inherit if all_selected => ExtMode::Exclude,
+        ExtMode::Inherit => ExtMode::Include,
+        ExtMode::Include | ExtMode::Exclude => ExtMode::Inherit,
+    }
+}
@@ -0,0 +1,248 @@
+use crate::lang;
+use serde::Serialize;
+use std::{fmt::Write as _, path::Path, str::FromStr};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExportFormat {
+    /// `// ===== START: path =====` delimiters.
+    #[default]
+    Plain,
+    /// One fenced code block per file, language inferred from the extension.
+    Markdown,
+    /// `<document path="...">` tags in the style of Anthropic's prompting guide.
+    Xml,
+    /// Array of `{path, language, bytes, tokens, content}` objects.
+    Json,
+}
+
+impl ExportFormat {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Plain => "plain",
+            Self::Markdown => "markdown",
+            Self::Xml => "xml",
+            Self::Json => "json",
+        }
+    }
+
+    #[must_use]
+    pub fn next(self) -> Self {
+        match self {
+            Self::Plain => Self::Markdown,
+            Self::Markdown => Self::Xml,
+            Self::Xml => Self::Json,
+            Self::Json => Self::Plain,
+        }
+    }
+
+    pub fn file_extension(self) -> &'static str {
+        match self {
+            Self::Plain => "txt",
+            Self::Markdown => "md",
+            Self::Xml => "xml",
+            Self::Json => "json",
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "plain" | "text" | "txt" => Ok(Self::Plain),
+            "markdown" | "md" => Ok(Self::Markdown),
+            "xml" => Ok(Self::Xml),
+            "json" => Ok(Self::Json),
+            other => Err(format!(
+                "unknown format `{other}` (expected plain, markdown, xml or json)"
+            )),
+        }
+    }
+}
+
+/// One file ready to be rendered; `content` is `Err` when it could not be read.
+#[derive(Debug, Clone)]
+pub struct Document {
+    pub path: String,
+    /// Qualifier shown after the path: `outline`, or a continuation marker for
+    /// files split across parts, e.g. `2/3, lines 401-800`.
+    pub note: Option<String>,
+    pub content: Result<String, String>,
+    pub tokens: usize,
+    /// Language tag override; `None` infers it from the path.
+    pub lang: Option<&'static str>,
+    /// One line about the file, written just above it.
+    pub description: Option<String>,
+}
+
+impl Document {
+    /// Path plus continuation note, as shown in delimiters and headers.
+    pub fn title(&self) -> String {
+        match &self.note {
+            Some(note) => format!("{} ({note})", self.path),
+            None => self.path.clone(),
+        }
+    }
+
+    pub fn language(&self) -> &'static str {
+        self.lang
+            .unwrap_or_else(|| lang::language(Path::new(&self.path)))
+    }
+}
+
+/// Render `docs`; `project` doubles as the header title, e.g. `app (part 2/3)`.
+pub fn render(format: ExportFormat, project: &str, docs: &[Document]) -> String {
+    match format {
+        ExportFormat::Plain => render_plain(project, docs),
+        ExportFormat::Markdown => render_markdown(project, docs),
+        ExportFormat::Xml => render_xml(project, docs),
+        ExportFormat::Json => render_json(docs),
+    }
+}
+
+fn render_plain(project: &str, docs: &[Document]) -> String {
+    let mut out = format!("// PAKR COMBINED TEXT EXPORT //\n// Project: {project}\n\n");
+    for doc in docs {
+        match &doc.content {
+            Ok(content) => {
+                if let Some(text) = &doc.description {
+                    let _ = writeln!(out, "// {text}");
+                }
+                let _ = writeln!(out, "// ===== START: {} =====", doc.title());
+                push_line_terminated(&mut out, content);
+                let _ = writeln!(out, "// ===== END: {} =====", doc.title());
+            }
+            Err(e) => {
+                let _ = writeln!(out, "// ERROR: {}: {e}", doc.path);
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn render_markdown(project: &str, docs: &[Document]) -> String {
+    let mut out = format!("# Project: {project}\n\n");
+    for doc in docs {
+        match &doc.content {
+            Ok(content) => {
+                let fence = fence_for(content);
+                let lang = doc.language();
+                if let Some(text) = &doc.description {
+                    let _ = writeln!(out, "> {text}\n");
+                }
+                let _ = writeln!(out, "{fence}{lang} {}", doc.title());
+                push_line_terminated(&mut out, content);
+                let _ = writeln!(out, "{fence}");
+            }
+            Err(e) => {
+                let _ = writeln!(out, "> ERROR: `{}`: {e}", doc.path);
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// A fence one backtick longer than the longest run inside the content.
+fn fence_for(content: &str) -> String {
+    let mut longest = 0;
+    let mut run = 0;
+    for c in content.chars() {
+        if c == '`' {
+            run += 1;
+            longest = longest.max(run);
+        } else {
+            run = 0;
+        }
+    }
+    "`".repeat((longest + 1).max(3))
+}
+
+fn render_xml(project: &str, docs: &[Document]) -> String {
+    let mut out = format!("<documents project=\"{}\">\n", xml_attr(project));
+    for doc in docs {
+        let path = xml_attr(&doc.path);
+        let note = doc
+            .note
+            .as_deref()
+            .map(|n| format!(" part=\"{}\"", xml_attr(n)))
+            .unwrap_or_default();
+        match &doc.content {
+            Ok(content) => {
+                let _ = writeln!(out, "<document path=\"{path}\"{note}>");
+                if let Some(text) = &doc.description {
+                    let _ = writeln!(out, "<description>{}</description>", xml_attr(text));
+                }
+                out.push_str("<content><![CDATA[\n");
+                push_line_terminated(&mut out, &cdata(content));
+                out.push_str("]]></content>\n</document>\n");
+            }
+            Err(e) => {
+                let _ = writeln!(out, "<document path=\"{path}\" error=\"{}\"/>", xml_attr(e));
+            }
+        }
+    }
+    out.push_str("</documents>\n");
+    out
+}
+
+/// File text safe inside a CDATA section: a `]]>` in the file closes the
+/// section and reopens it between `]]` and `>`.
+fn cdata(s: &str) -> String {
+    s.replace("]]>", "]]]]><![CDATA[>")
+}
+
+fn xml_attr(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[derive(Serialize)]
+struct JsonDoc<'a> {
+    path: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    part: Option<&'a str>,
+    language: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<&'a str>,
+    bytes: usize,
+    tokens: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<&'a str>,
+}
+
+fn render_json(docs: &[Document]) -> String {
+    let items: Vec<JsonDoc> = docs
+        .iter()
+        .map(|doc| {
+            let content = doc.content.as_deref().ok();
+            let bytes = content.map_or(0, str::len);
+            JsonDoc {
+                path: &doc.path,
+                part: doc.note.as_deref(),
+                language: doc.language(),
+                description: doc.description.as_deref(),
+                bytes,
+                tokens: doc.tokens,
+                content,
+                error: doc.content.as_ref().err().map(String::as_str),
+            }
+        })
+        .collect();
+    let mut out = serde_json::to_string_pretty(&items).unwrap_or_else(|_| "[]".to_string());
+    out.push('\n');
+    out
+}
+
+fn push_line_terminated(out: &mut String, content: &str) {
+    out.push_str(content);
+    if !content.ends_with('\n') {
+        out.push('\n');
+    }
+}
@@ -0,0 +1,137 @@
+use std::path::PathBuf;
+
+/// One candidate that matched the query.
+#[derive(Debug, Clone)]
+pub struct Match {
+    pub path: PathBuf,
+    pub score: i64,
+    /// Char indices of the matched characters in the path's display form.
+    pub positions: Vec<usize>,
+}
+
+const MATCH: i64 = 16;
+const CONSECUTIVE: i64 = 12;
+const BOUNDARY: i64 = 10;
+const CAMEL: i64 = 6;
+const BASENAME: i64 = 8;
+const GAP: i64 = 1;
+
+/// Best `limit` matches of `query` among `paths`, highest score first.
+/// An empty query lists every path, shortest first.
+pub fn search(query: &str, paths: &[PathBuf], limit: usize) -> Vec<Match> {
+    let query: Vec<char> = query.chars().filter(|c| !c.is_whitespace()).collect();
+    let mut matches: Vec<Match> = paths
+        .iter()
+        .filter_map(|path| {
+            let text = path.to_string_lossy();
+            let (score, positions) = score(&query, &text)?;
+            Some(Match {
+                path: path.clone(),
+                score,
+                positions,
+            })
+        })
+        .collect();
+    matches.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| a.path.as_os_str().len().cmp(&b.path.as_os_str().len()))
+            .then_with(|| a.path.cmp(&b.path))
+    });
+    matches.truncate(limit);
+    matches
+}
+
+/// Case-insensitive subsequence match with the alignment that scores best:
+/// consecutive runs, matches at word or path-segment starts and matches in
+/// the file name are rewarded, gaps are penalized.
+pub fn score(query: &[char], text: &str) -> Option<(i64, Vec<usize>)> {
+    let chars: Vec<char> = text.chars().collect();
+    if query.is_empty() {
+        return Some((0, Vec::new()));
+    }
+    if query.len() > chars.len() {
+        return None;
+    }
+    let base = chars.iter().rposition(|&c| c == '/').map_or(0, |p| p + 1);
+    let bonus: Vec<i64> = (0..chars.len())
+        .map(|j| position_bonus(&chars, j, base))
+        .collect();
+    let n = chars.len();
+
+    // best[i][j]: top score with query[i] matched at text[j]; from[i][j]: where query[i-1] sat.
+    let mut best = vec![vec![None::<i64>; n]; query.len()];
+    let mut from = vec![vec![0usize; n]; query.len()];
+    for (i, &q) in query.iter().enumerate() {
+        // Best query[i-1] match at least two left of j, charged for the gap.
+        let mut carry: Option<(i64, usize)> = None;
+        for j in 0..n {
+            let prev = if i == 0 {
+                Some((0, 0))
+            } else {
+                prev_score(&best, i, j)
+                    .map(|(s, k)| (s + CONSECUTIVE, k))
+                    .max(carry)
+            };
+            if eq_fold(q, chars.get(j).copied()) {
+                if let Some((s, k)) = prev {
+                    set(
+                        &mut best,
+                        i,
+                        j,
+                        Some(s + MATCH + bonus.get(j).copied().unwrap_or(0)),
+                    );
+                    set(&mut from, i, j, k);
+                }
+            }
+            if i > 0 {
+                let here = prev_score(&best, i, j);
+                carry = carry.max(here).map(|(s, k)| (s - GAP, k));
+            }
+        }
+    }
+
+    let last = query.len() - 1;
+    let (score, end) = (0..n)
+        .filter_map(|j| Some((best.get(last)?.get(j).copied()??, j)))
+        .max_by_key(|&(s, j)| (s, std::cmp::Reverse(j)))?;
+    let mut positions = vec![end];
+    let mut j = end;
+    for i in (1..=last).rev() {
+        j = from.get(i).and_then(|row| row.get(j)).copied().unwrap_or(0);
+        positions.push(j);
+    }
+    positions.reverse();
+    Some((score, positions))
+}
+
+/// Score of query[i-1] matched at text[j-1] (the cell diagonally before `j`).
+fn prev_score(best: &[Vec<Option<i64>>], i: usize, j: usize) -> Option<(i64, usize)> {
+    let k = j.checked_sub(1)?;
+    let s = best.get(i - 1)?.get(k).copied()??;
+    Some((s, k))
+}
+
+fn position_bonus(chars: &[char], j: usize, base: usize) -> i64 {
+    let cur = chars.get(j).copied().unwrap_or(' ');
+    let prev = j.checked_sub(1).and_then(|p| chars.get(p)).copied();
+    let mut bonus = match prev {
+        None | Some('/' | '\\' | '_' | '-' | '.' | ' ') => BOUNDARY,
+        Some(p) if p.is_lowercase() && cur.is_uppercase() => CAMEL,
+        _ => 0,
+    };
+    if j >= base {
+        bonus += BASENAME;
+    }
+    bonus
+}
+
+fn eq_fold(q: char, c: Option<char>) -> bool {
+    c.is_some_and(|c| c == q || c.to_lowercase().eq(q.to_lowercase()))
+}
+
+fn set<T>(grid: &mut [Vec<T>], i: usize, j: usize, value: T) {
+    if let Some(cell) = grid.get_mut(i).and_then(|row| row.get_mut(j)) {
+        *cell = value;
+    }
+}
@@ -0,0 +1,173 @@
+use anyhow::{Context, Result};
+use gix::{
+    bstr::{BStr, ByteSlice},
+    status::{index_worktree, plumbing::index_as_worktree::EntryStatus, Item, UntrackedFiles},
+};
+use std::path::{Path, PathBuf};
+
+/// Working-tree changes, as paths relative to the scanned root.
+#[derive(Debug, Default)]
+pub struct Changes {
+    /// Tracked files whose worktree content differs from the index.
+    pub modified: Vec<PathBuf>,
+    /// Files whose index entry differs from `HEAD`.
+    pub staged: Vec<PathBuf>,
+    pub untracked: Vec<PathBuf>,
+}
+
+/// Status of the repository containing `root`, read straight from `.git`.
+/// `Ok(None)` when `root` is not inside a git work tree.
+pub fn status(root: &Path) -> Result<Option<Changes>> {
+    let Some(repo) = discover(root) else {
+        return Ok(None);
+    };
+    let base = Base::new(&repo, root)?;
+    let mut changes = Changes::default();
+    let iter = repo
+        .status(gix::progress::Discard)?
+        .untracked_files(UntrackedFiles::Files)
+        .into_iter(None)?;
+    for item in iter {
+        let item = item?;
+        let Some(path) = base.relative(item.location()) else {
+            continue;
+        };
+        match item {
+            Item::TreeIndex(_) => changes.staged.push(path),
+            Item::IndexWorktree(change) if is_untracked(&change) => changes.untracked.push(path),
+            Item::IndexWorktree(change) if is_modified(&change) => changes.modified.push(path),
+            Item::IndexWorktree(_) => {}
+        }
+    }
+    for list in [
+        &mut changes.modified,
+        &mut changes.staged,
+        &mut changes.untracked,
+    ] {
+        list.sort();
+        list.dedup();
+    }
+    Ok(Some(changes))
+}
+
+/// Files that differ between `rev` and the working tree, staged or not —
+/// the file list of `git diff <rev>`. Untracked files are not included.
+pub fn since(root: &Path, rev: &str) -> Result<Vec<PathBuf>> {
+    changed(root, rev, false)
+}
+
+/// [`since`] plus untracked files, which a diff shows as new.
+pub fn since_with_untracked(root: &Path, rev: &str) -> Result<Vec<PathBuf>> {
+    changed(root, rev, true)
+}
+
+fn changed(root: &Path, rev: &str, untracked: bool) -> Result<Vec<PathBuf>> {
+    let repo =
+        discover(root).with_context(|| format!("{} is not in a git repository", root.display()))?;
+    let base = Base::new(&repo, root)?;
+    let spec = format!("{rev}^{{tree}}");
+    let tree = repo
+        .rev_parse_single(spec.as_str())
+        .with_context(|| format!("unknown revision '{rev}'"))?;
+    let iter = repo
+        .status(gix::progress::Discard)?
+        .untracked_files(if untracked {
+            UntrackedFiles::Files
+        } else {
+            UntrackedFiles::None
+        })
+        .head_tree(tree.detach())
+        .into_iter(None)?;
+    let mut files = Vec::new();
+    for item in iter {
+        let item = item?;
+        let counts = match &item {
+            Item::TreeIndex(_) => true,
+            Item::IndexWorktree(change) => is_modified(change) || is_untracked(change),
+        };
+        if let (true, Some(path)) = (counts, base.relative(item.location())) {
+            files.push(path);
+        }
+    }
+    files.sort();
+    files.dedup();
+    Ok(files)
+}
+
+/// A revision's tree, read back file by file for diffing.
+pub struct Revision {
+    repo: gix::Repository,
+    tree: gix::ObjectId,
+    base: Base,
+}
+
+impl Revision {
+    pub fn open(root: &Path, rev: &str) -> Result<Self> {
+        let repo = discover(root)
+            .with_context(|| format!("{} is not in a git repository", root.display()))?;
+        let base = Base::new(&repo, root)?;
+        let spec = format!("{rev}^{{tree}}");
+        let tree = repo
+            .rev_parse_single(spec.as_str())
+            .with_context(|| format!("unknown revision '{rev}'"))?
+            .detach();
+        Ok(Self { repo, tree, base })
+    }
+
+    /// Content of `rel` (relative to the scanned root) at this revision,
+    /// `None` when the file did not exist there.
+    pub fn read(&self, rel: &Path) -> Result<Option<Vec<u8>>> {
+        let tree = self.repo.find_object(self.tree)?.into_tree();
+        let Some(entry) = tree.lookup_entry_by_path(self.base.prefix.join(rel))? else {
+            return Ok(None);
+        };
+        if !entry.mode().is_blob() {
+            return Ok(None);
+        }
+        Ok(Some(entry.object()?.detach().data))
+    }
+}
+
+fn discover(root: &Path) -> Option<gix::Repository> {
+    gix::discover(root).ok().filter(|r| r.workdir().is_some())
+}
+
+/// Maps repository-relative paths onto paths relative to the scanned root,
+/// which may be a subfolder of the work tree.
+struct Base {
+    prefix: PathBuf,
+}
+
+impl Base {
+    fn new(repo: &gix::Repository, root: &Path) -> Result<Self> {
+        let workdir = repo.workdir().context("bare repository")?.canonicalize()?;
+        let prefix = root
+            .strip_prefix(&workdir)
+            .unwrap_or(Path::new(""))
+            .to_path_buf();
+        Ok(Self { prefix })
+    }
+
+    fn relative(&self, location: &BStr) -> Option<PathBuf> {
+        let path = location.to_path().ok()?;
+        path.strip_prefix(&self.prefix).ok().map(Path::to_path_buf)
+    }
+}
+
+fn is_untracked(change: &index_worktree::Item) -> bool {
+    matches!(
+        change,
+        index_worktree::Item::DirectoryContents { entry, .. }
+            if entry.status == gix::dir::entry::Status::Untracked
+    )
+}
+
+fn is_modified(change: &index_worktree::Item) -> bool {
+    match change {
+        index_worktree::Item::Modification { status, .. } => {
+            !matches!(status, EntryStatus::NeedsUpdate(_))
+        }
+        index_worktree::Item::Rewrite { .. } => true,
+        index_worktree::Item::DirectoryContents { .. } => false,
+    }
+}
@@ -0,0 +1,127 @@
+use crate::{
+    scanner,
+    types::{AppState, CategoryKind},
+};
+use anyhow::{bail, Context, Result};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use std::{collections::HashSet, path::PathBuf};
+
+/// Selection flags for `pakr pack`.
+#[derive(Debug, Clone, Default)]
+pub struct PackOptions {
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    /// Files exported in full when outline mode is on.
+    pub full: Vec<String>,
+    pub layers: Option<Vec<u8>>,
+    pub no_gitignore: bool,
+    /// `--changed` or `--since` already picked the git categories; `--layer`
+    /// and `--include` then narrow those files instead of replacing them.
+    pub changed: bool,
+}
+
+/// Turn command-line flags into the same selection the TUI would build.
+pub fn apply(state: &mut AppState, opts: &PackOptions) -> Result<()> {
+    if opts.no_gitignore {
+        state.respect_gitignore = false;
+    }
+    let include = build_set(&opts.include)?;
+    let exclude = build_set(&opts.exclude)?;
+    let full = build_set(&opts.full)?;
+    if opts.changed {
+        narrow_changed(state, opts, &include)?;
+    } else if let Some(layers) = &opts.layers {
+        apply_layers(state, layers)?;
+    } else if !opts.include.is_empty() {
+        for cat in &mut state.categories {
+            cat.enabled = false;
+        }
+    }
+
+    for path in scanner::all_files(&state.tree) {
+        if full.is_match(&path) {
+            state.full_files.push(path.clone());
+        }
+        if exclude.is_match(&path) {
+            state.exclude.insert(path);
+        } else if include.is_match(&path)
+            && !opts.changed
+            && !(state.respect_gitignore && state.gitignore_filter.is_ignored(&path))
+        {
+            state.manual_includes.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Exclude the changed files that fall outside `--layer` or `--include`.
+fn narrow_changed(state: &mut AppState, opts: &PackOptions, include: &GlobSet) -> Result<()> {
+    let in_layers = match &opts.layers {
+        Some(layers) => Some(layer_files(state, layers)?),
+        None => None,
+    };
+    let outside: Vec<PathBuf> = state
+        .selected_paths()
+        .into_iter()
+        .filter(|p| {
+            in_layers.as_ref().is_some_and(|files| !files.contains(p))
+                || (!opts.include.is_empty() && !include.is_match(p))
+        })
+        .collect();
+    state.exclude.extend(outside);
+    Ok(())
+}
+
+fn layer_files(state: &AppState, layers: &[u8]) -> Result<HashSet<PathBuf>> {
+    if !state.has_semmap {
+        bail!("--layer needs a SEMMAP.md in the project root");
+    }
+    Ok(state
+        .categories
+        .iter()
+        .filter(|c| matches!(c.kind, CategoryKind::SemmapLayer { index, .. } if layers.contains(&index)))
+        .flat_map(|c| c.files.iter().cloned())
+        .collect())
+}
+
+fn apply_layers(state: &mut AppState, layers: &[u8]) -> Result<()> {
+    if !state.has_semmap {
+        bail!("--layer needs a SEMMAP.md in the project root");
+    }
+    for cat in &mut state.categories {
+        if let CategoryKind::SemmapLayer { index, .. } = cat.kind {
+            cat.enabled = layers.contains(&index);
+        }
+    }
+    Ok(())
+}
+
+fn build_set(patterns: &[String]) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for pat in patterns {
+        builder.add(Glob::new(pat).with_context(|| format!("invalid glob `{pat}`"))?);
+    }
+    Ok(builder.build()?)
+}
+
+/// Parse `0-2`, `3` or `0,2,4` (and mixes like `0-1,4`) into layer indices.
+pub fn parse_layers(spec: &str) -> Result<Vec<u8>> {
+    let mut out = Vec::new();
+    for part in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        if let Some((lo, hi)) = part.split_once('-') {
+            out.extend(layer_index(lo, part)?..=layer_index(hi, part)?);
+        } else {
+            out.push(layer_index(part, part)?);
+        }
+    }
+    if out.is_empty() {
+        bail!("empty layer list");
+    }
+    Ok(out)
+}
+
+fn layer_index(s: &str, part: &str) -> Result<u8> {
+    s.trim()
+        .parse()
+        .with_context(|| format!("bad layer `{part}`"))
+}
@@ -0,0 +1,184 @@
+use crate::{snapshot::Snapshot, types::AppState};
+use std::path::{Path, PathBuf};
+
+/// Steps kept; the oldest fall off first.
+const LIMIT: usize = 100;
+
+/// Undo and redo stacks of whole selections, so any change, however it was
+/// made, can be stepped back.
+#[derive(Debug, Default)]
+pub struct History {
+    undo: Vec<Entry>,
+    redo: Vec<Entry>,
+}
+
+#[derive(Debug)]
+struct Entry {
+    /// What the change did, e.g. "exclude src/main.rs".
+    label: String,
+    /// The selection to go back to.
+    selection: Snapshot,
+}
+
+/// Run an action that can change the selection and record the change it
+/// made, if any. Only such actions are wrapped, so cursor moves and hovers
+/// never pay for the two snapshots. A change also drops the export parts
+/// queued for `p`, which no longer match the selection.
+pub fn track<R>(state: &mut AppState, f: impl FnOnce(&mut AppState) -> R) -> R {
+    let before = Snapshot::capture(state);
+    let result = f(state);
+    let after = Snapshot::capture(state);
+    if before != after {
+        state.pending_parts.clear();
+        let history = &mut state.history;
+        history.undo.push(Entry {
+            label: describe(&before, &after),
+            selection: before,
+        });
+        if history.undo.len() > LIMIT {
+            history.undo.remove(0);
+        }
+        history.redo.clear();
+    }
+    result
+}
+
+pub fn undo(state: &mut AppState) {
+    let Some(entry) = state.history.undo.pop() else {
+        state.status = "nothing to undo".to_string();
+        return;
+    };
+    let label = entry.label.clone();
+    let current = swap(state, entry);
+    state.history.redo.push(current);
+    state.status = format!("undid: {label}  [ctrl-r] redo");
+}
+
+pub fn redo(state: &mut AppState) {
+    let Some(entry) = state.history.redo.pop() else {
+        state.status = "nothing to redo".to_string();
+        return;
+    };
+    let label = entry.label.clone();
+    let current = swap(state, entry);
+    state.history.undo.push(current);
+    state.status = format!("redid: {label}  [u] undo");
+}
+
+/// Apply `entry`, returning the selection it replaced under the same label.
+fn swap(state: &mut AppState, entry: Entry) -> Entry {
+    let current = Snapshot::capture(state);
+    entry.selection.apply(state);
+    state.pending_parts.clear();
+    Entry {
+        label: entry.label,
+        selection: current,
+    }
+}
+
+/// A short description of what changed between two selections.
+fn describe(before: &Snapshot, after: &Snapshot) -> String {
+    let mut parts = Vec::new();
+    parts.extend(names(
+        ["category", "categories"],
+        &before.categories,
+        &after.categories,
+    ));
+    parts.extend(names(
+        ["subsystem", "subsystems"],
+        before.subsystems.as_deref().unwrap_or_default(),
+        after.subsystems.as_deref().unwrap_or_default(),
+    ));
+    parts.extend(names(
+        ["include type", "include types"],
+        &before.include_types,
+        &after.include_types,
+    ));
+    parts.extend(names(
+        ["exclude type", "exclude types"],
+        &before.exclude_types,
+        &after.exclude_types,
+    ));
+    parts.extend(paths("exclude", &before.exclude, &after.exclude));
+    parts.extend(paths("include", &before.include, &after.include));
+    parts.extend(paths(
+        "add",
+        &before.manual_includes,
+        &after.manual_includes,
+    ));
+    parts.extend(paths("keep full", &before.full_files, &after.full_files));
+    if before.respect_gitignore != after.respect_gitignore {
+        parts.push(format!("gitignore {}", on_off(after.respect_gitignore)));
+    }
+    if before.outline != after.outline {
+        parts.push(format!("outline {}", on_off(after.outline)));
+    }
+    if before.tag != after.tag {
+        parts.push(
+            after
+                .tag
+                .as_ref()
+                .map_or_else(|| "tag filter off".to_string(), |t| format!("tag [{t}]")),
+        );
+    }
+    if before.describe != after.describe {
+        parts.push(format!("descriptions {}", on_off(after.describe)));
+    }
+    if before.format != after.format {
+        parts.push(format!("format {}", after.format));
+    }
+    let extra = parts.len().saturating_sub(2);
+    parts.truncate(2);
+    match (parts.is_empty(), extra) {
+        (true, _) => "selection change".to_string(),
+        (false, 0) => parts.join(", "),
+        (false, n) => format!("{} and {n} more", parts.join(", ")),
+    }
+}
+
+/// "category Docs on", or counts when several flipped at once.
+fn names(what: [&str; 2], before: &[String], after: &[String]) -> Option<String> {
+    let [one_of, many] = what;
+    let on: Vec<&String> = after.iter().filter(|n| !before.contains(n)).collect();
+    let off: Vec<&String> = before.iter().filter(|n| !after.contains(n)).collect();
+    match (on.as_slice(), off.as_slice()) {
+        ([], []) => None,
+        ([one], []) => Some(format!("{one_of} {one} on")),
+        ([], [one]) => Some(format!("{one_of} {one} off")),
+        (_, []) => Some(format!("{} {many} on", on.len())),
+        ([], _) => Some(format!("{} {many} off", off.len())),
+        _ => Some(format!("{many}: {} on, {} off", on.len(), off.len())),
+    }
+}
+
+/// "exclude src/a.rs", "un-exclude src/a.rs", or counts when several moved.
+fn paths(what: &str, before: &[PathBuf], after: &[PathBuf]) -> Option<String> {
+    let added: Vec<&Path> = new_in(after, before);
+    let removed: Vec<&Path> = new_in(before, after);
+    match (added.as_slice(), removed.as_slice()) {
+        ([], []) => None,
+        ([one], []) => Some(format!("{what} {}", one.display())),
+        ([], [one]) => Some(format!("un-{what} {}", one.display())),
+        _ => Some(format!(
+            "{what}: {} added, {} removed",
+            added.len(),
+            removed.len()
+        )),
+    }
+}
+
+fn new_in<'a>(items: &'a [PathBuf], other: &[PathBuf]) -> Vec<&'a Path> {
+    items
+        .iter()
+        .filter(|p| !other.contains(p))
+        .map(PathBuf::as_path)
+        .collect()
+}
+
+fn on_off(on: bool) -> &'static str {
+    if on {
+        "on"
+    } else {
+        "off"
+    }
+}
@@ -1,8 +1,30 @@
+pub mod analyzer;
+pub mod app;
+pub mod bpe;
+pub mod budget;
 pub mod categories;
+pub mod config;
+pub mod deps;
+pub mod diff;
+pub mod extensions;
+pub mod format;
+pub mod fuzzy;
+pub mod git;
+pub mod headless;
+pub mod history;
+pub mod lang;
+pub mod outline;
 pub mod output;
 pub mod packer;
+pub mod path_list;
+pub mod profile;
 pub mod scanner;
+pub mod selection;
 pub mod semmap;
+pub mod session;
+pub mod snapshot;
+pub mod split;
 pub mod subsystems;
+pub mod tokens;
 pub mod tui;
 pub mod types;
@@ -1,76 +1,211 @@
-use anyhow::Result;
-use clap::Parser;
-use ignore::gitignore::GitignoreBuilder;
-use pakr::{categories, scanner, semmap, subsystems, types};
-use ratatui::{layout::Rect, widgets::ListState};
-use std::path::PathBuf;
+mod cli;
 
-#[derive(Parser, Debug)]
-#[command(name = "pakr", about = "Terminal file packer for AI context")]
-struct Args {
-    #[arg(default_value = ".")]
-    path: PathBuf,
-}
+use anyhow::{bail, Result};
+use clap::Parser;
+use cli::{Args, Command, CommonArgs, PackArgs, SemmapArgs, SemmapCommand};
+use pakr::{
+    app,
+    bpe::Bpe,
+    budget,
+    diff::{DiffMode, DiffOptions},
+    headless, output, packer, profile,
+    semmap::{self, SemmapData},
+    session,
+    tokens::TokenCounter,
+    types::AppState,
+};
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+};
 
 fn main() -> Result<()> {
     let args = Args::parse();
-    let root = args.path.canonicalize()?;
-
-    let scan = scanner::scan(&root)?;
-    let semmap_data = semmap::load(&root).ok();
-    let has_semmap = semmap_data.is_some();
+    match args.command {
+        Some(Command::Pack(pack)) => run_pack(&pack),
+        Some(Command::Semmap {
+            command: SemmapCommand::Init(init),
+        }) => run_semmap_init(&init),
+        Some(Command::Semmap {
+            command: SemmapCommand::Check { path },
+        }) => run_semmap_check(&path),
+        None => {
+            let mut state = load(&args.path, &args.common, true)?;
+            if args.common.profile.is_none() && !args.fresh {
+                session::restore(&mut state);
+            }
+            configure(&mut state, &args.common)?;
+            pakr::tui::run(state)
+        }
+    }
+}
 
-    let (cats, subs) = if let Some(ref sd) = semmap_data {
-        (
-            categories::from_semmap(sd, &scan.tree),
-            subsystems::build(sd),
-        )
+fn run_pack(args: &PackArgs) -> Result<()> {
+    let mut state = load(&args.path, &args.common, false)?;
+    let opts = headless::PackOptions {
+        include: args.include.clone(),
+        exclude: args.exclude.clone(),
+        full: args.full.clone(),
+        layers: args
+            .layer
+            .as_deref()
+            .map(headless::parse_layers)
+            .transpose()?,
+        no_gitignore: args.no_gitignore,
+        changed: args.common.changed || args.common.since.is_some(),
+    };
+    configure(&mut state, &args.common)?;
+    headless::apply(&mut state, &opts)?;
+    check_budget(&mut state, args.fit);
+    let parts = packer::build_parts(&state, true)?;
+    if let Some(path) = &args.output {
+        let written = output::to_files(&parts, path)?;
+        let n = state.selected_paths().len();
+        for p in &written {
+            eprintln!("pakr: wrote {}", p.display());
+        }
+        eprintln!("pakr: {n} files in {} part(s)", written.len());
     } else {
-        (categories::from_heuristics(&scan.tree), Vec::new())
+        let mut out = std::io::stdout().lock();
+        for part in &parts {
+            out.write_all(part.as_bytes())?;
+        }
+    }
+    Ok(())
+}
+
+fn run_semmap_init(args: &SemmapArgs) -> Result<()> {
+    let root = args.path.canonicalize()?;
+    let generated = semmap::generate::generate(&root)?;
+    if args.stdout {
+        std::io::stdout().write_all(generated.text.as_bytes())?;
+        return Ok(());
+    }
+    let path = match (&args.output, &generated.path) {
+        (Some(output), _) => output.clone(),
+        (None, Some(path)) => root.join(path),
+        (None, None) if generated.sources.len() > 1 => bail!(
+            "the SEMMAP is split over {}; pass --output FILE or --stdout to get it as one file",
+            list_paths(&generated.sources)
+        ),
+        (None, None) => {
+            bail!("the `semmap` setting only has globs; pass --output FILE or --stdout")
+        }
     };
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(&path, &generated.text)?;
+    eprintln!(
+        "pakr: wrote {} ({} files, {} edges)",
+        path.display(),
+        generated.files,
+        generated.edges
+    );
+    Ok(())
+}
 
-    let gi_path = root.join(".gitignore");
-    let has_gitignore = gi_path.exists();
-    let gitignore_filter = if has_gitignore {
-        let mut builder = GitignoreBuilder::new(&root);
-        builder.add(&gi_path);
-        builder
-            .build()
-            .ok()
-            .map(types::GitignoreFilter::new)
-            .unwrap_or_default()
+fn list_paths(paths: &[PathBuf]) -> String {
+    let names: Vec<String> = paths.iter().map(|p| p.display().to_string()).collect();
+    names.join(", ")
+}
+
+fn run_semmap_check(path: &Path) -> Result<()> {
+    let issues = semmap::check::run(&path.canonicalize()?)?;
+    for issue in &issues {
+        println!("{}", issue.render());
+    }
+    if !issues.is_empty() {
+        bail!("{} SEMMAP problem(s)", issues.len());
+    }
+    eprintln!("pakr: SEMMAP ok");
+    Ok(())
+}
+
+/// Scan and apply `--profile` before any other flag refines the selection.
+/// `background` leaves import analysis running for the TUI to pick up.
+fn load(path: &Path, common: &CommonArgs, background: bool) -> Result<AppState> {
+    let mut state = if background {
+        app::open(path)?
     } else {
-        types::GitignoreFilter::default()
+        app::load(path)?
     };
+    if let Some(name) = &common.profile {
+        for missing in profile::load(&mut state, name)? {
+            eprintln!(
+                "pakr: profile '{name}': {} no longer exists, dropped",
+                missing.display()
+            );
+        }
+    }
+    Ok(state)
+}
 
-    let state = types::AppState {
-        root,
-        tree: scan.tree,
-        file_sizes: scan.file_sizes,
-        categories: cats,
-        subsystems: subs,
-        exclude: types::ExcludeList::default(),
-        manual_includes: Vec::new(),
-        has_semmap,
-        has_gitignore,
-        respect_gitignore: has_gitignore,
-        gitignore_filter,
-        all_collapsed: false,
-        focus: types::Focus::Categories,
-        cat_cursor: 0,
-        sub_cursor: 0,
-        exclude_cursor: 0,
-        hover_path: None,
-        input_mode: false,
-        input_buffer: String::new(),
-        cat_list_state: ListState::default(),
-        sub_list_state: ListState::default(),
-        tree_list_state: ListState::default(),
-        cat_area: Rect::default(),
-        sub_area: Rect::default(),
-        tree_area: Rect::default(),
-        status: String::from("ready"),
+fn configure(state: &mut AppState, common: &CommonArgs) -> Result<()> {
+    if let Some(format) = common.format {
+        state.format = format;
+    }
+    if common.budget.is_some() {
+        state.budget = common.budget;
+    }
+    state.part_limit = common.split;
+    state.outline = common.outline;
+    if common.describe {
+        state.describe = true;
+    }
+    if let Some(tag) = &common.tag {
+        let tag = tag.trim_matches(['[', ']']).to_string();
+        let tags = state.graph.as_ref().map_or_else(Vec::new, SemmapData::tags);
+        if !tags.contains(&tag) {
+            bail!(
+                "no file is tagged [{tag}] in SEMMAP.md (tags: {})",
+                tags.join(", ")
+            );
+        }
+        state.tag_filter = Some(tag);
+    }
+    state.diff = DiffOptions {
+        mode: if common.diff_only {
+            DiffMode::Only
+        } else if common.diff {
+            DiffMode::Append
+        } else {
+            DiffMode::Off
+        },
+        base: common
+            .diff_base
+            .clone()
+            .or_else(|| common.since.clone())
+            .unwrap_or_else(|| "HEAD".to_string()),
+        context: common.unified,
     };
+    if let Some(rev) = &common.since {
+        app::track_since(state, rev)?;
+    }
+    if common.changed || common.since.is_some() {
+        app::select_changed(state, common.since.is_some());
+    }
+    if let Some(path) = &common.tokenizer {
+        state.tokens = TokenCounter::new(Box::new(Bpe::from_file(path)?));
+    }
+    Ok(())
+}
 
-    pakr::tui::run(state)
+fn check_budget(state: &mut AppState, fit: bool) {
+    let Some(limit) = state.budget else {
+        return;
+    };
+    if fit {
+        for path in budget::auto_fit(state) {
+            eprintln!("pakr: dropped {}", path.display());
+        }
+    }
+    let total = state.total_tokens();
+    if total > limit {
+        eprintln!(
+            "pakr: selection is ~{} tok, over the {} budget",
+            budget::fmt_tokens(total),
+            budget::fmt_tokens(limit)
+        );
+    }
 }
@@ -0,0 +1,224 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lang {
+    Rust,
+    /// JavaScript and TypeScript.
+    Script,
+}
+
+const ELIDED: &str = "{ ... }";
+
+pub fn outline(src: &str, lang: Lang) -> String {
+    let chars: Vec<char> = src.chars().collect();
+    let mut out = String::with_capacity(src.len());
+    let mut i = 0;
+    // Rust only: inside a `fn` signature, waiting for its body or `;`.
+    let mut pending_fn = false;
+    let mut depth = 0i32;
+
+    while let Some(&c) = chars.get(i) {
+        if let Some(end) = literal_end(&chars, i, lang) {
+            out.extend(chars.get(i..end).unwrap_or_default());
+            i = end;
+            continue;
+        }
+        if lang == Lang::Rust && is_word_start(&chars, i) {
+            let end = word_end(&chars, i);
+            let word: String = chars.get(i..end).unwrap_or_default().iter().collect();
+            if word == "fn" && next_is_ident(&chars, end) {
+                pending_fn = true;
+                depth = 0;
+            }
+            out.push_str(&word);
+            i = end;
+            continue;
+        }
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth -= 1,
+            ';' if depth <= 0 => pending_fn = false,
+            '{' if opens_body(lang, pending_fn && depth <= 0, &out) => {
+                out.push_str(ELIDED);
+                i = matching_brace(&chars, i, lang);
+                pending_fn = false;
+                continue;
+            }
+            _ => {}
+        }
+        out.push(c);
+        i += 1;
+    }
+    out
+}
+
+fn opens_body(lang: Lang, rust_pending: bool, out: &str) -> bool {
+    match lang {
+        Lang::Rust => rust_pending,
+        Lang::Script => script_body(out),
+    }
+}
+
+/// A `{` right after `)`, `=>` or a `): Type` return annotation opens a
+/// function or method body, unless the statement is a class header.
+fn script_body(out: &str) -> bool {
+    let start = out.rfind([';', '{', '}']).map_or(0, |p| p + 1);
+    let stmt = out.get(start..).unwrap_or("").trim();
+    if stmt.starts_with("class ") || stmt.contains(" class ") {
+        return false;
+    }
+    if stmt.ends_with(')') || stmt.ends_with("=>") {
+        return true;
+    }
+    stmt.rfind(')')
+        .and_then(|p| stmt.get(p + 1..))
+        .is_some_and(|rest| rest.trim_start().starts_with(':'))
+}
+
+/// Index just past the `}` matching the `{` at `open`.
+fn matching_brace(chars: &[char], open: usize, lang: Lang) -> usize {
+    let mut depth = 0usize;
+    let mut i = open;
+    while let Some(&c) = chars.get(i) {
+        if let Some(end) = literal_end(chars, i, lang) {
+            i = end;
+            continue;
+        }
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth = depth.saturating_sub(1);
+                if depth == 0 {
+                    return i + 1;
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    chars.len()
+}
+
+/// If a comment or string literal starts at `i`, the index just past it.
+fn literal_end(chars: &[char], i: usize, lang: Lang) -> Option<usize> {
+    let c = *chars.get(i)?;
+    let next = chars.get(i + 1).copied();
+    match (c, next) {
+        ('/', Some('/')) => Some(find_from(chars, i, |ch| ch == '\n').unwrap_or(chars.len())),
+        ('/', Some('*')) => Some(block_comment_end(chars, i + 2)),
+        ('"' | '\'', _) if lang == Lang::Script => line_quoted_end(chars, i, c),
+        ('"', _) => Some(quoted_end(chars, i, '"')),
+        ('`', _) if lang == Lang::Script => Some(quoted_end(chars, i, '`')),
+        ('\'', _) => rust_char_end(chars, i),
+        ('r', Some('"' | '#')) if lang == Lang::Rust && !prev_is_ident(chars, i) => {
+            raw_string_end(chars, i + 1)
+        }
+        _ => None,
+    }
+}
+
+fn quoted_end(chars: &[char], open: usize, quote: char) -> usize {
+    let mut i = open + 1;
+    while let Some(&c) = chars.get(i) {
+        if c == '\\' {
+            i += 2;
+            continue;
+        }
+        if c == quote {
+            return i + 1;
+        }
+        i += 1;
+    }
+    chars.len()
+}
+
+/// A JS `'...'` or `"..."` string, which cannot span lines; `None` when
+/// the line ends first, as for the apostrophe in JSX text like `Don't`.
+fn line_quoted_end(chars: &[char], open: usize, quote: char) -> Option<usize> {
+    let mut i = open + 1;
+    while let Some(&c) = chars.get(i) {
+        match c {
+            '\\' => i += 2,
+            '\n' => return None,
+            _ if c == quote => return Some(i + 1),
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+fn block_comment_end(chars: &[char], from: usize) -> usize {
+    let mut i = from;
+    while let Some(&c) = chars.get(i) {
+        if c == '*' && chars.get(i + 1) == Some(&'/') {
+            return i + 2;
+        }
+        i += 1;
+    }
+    chars.len()
+}
+
+/// `'x'` and `'\n'` are char literals; `'a` on its own is a lifetime.
+fn rust_char_end(chars: &[char], i: usize) -> Option<usize> {
+    match (chars.get(i + 1), chars.get(i + 2)) {
+        (Some('\\'), _) => Some(quoted_end(chars, i, '\'')),
+        (Some(_), Some('\'')) => Some(i + 3),
+        _ => None,
+    }
+}
+
+/// `r"..."`, `r#"..."#` and friends; `hashes_at` points just past the `r`.
+fn raw_string_end(chars: &[char], hashes_at: usize) -> Option<usize> {
+    let hashes = chars
+        .get(hashes_at..)?
+        .iter()
+        .take_while(|&&c| c == '#')
+        .count();
+    let quote = hashes_at + hashes;
+    if chars.get(quote) != Some(&'"') {
+        return None;
+    }
+    let mut i = quote + 1;
+    while let Some(&c) = chars.get(i) {
+        let closes = (1..=hashes).all(|k| chars.get(i + k) == Some(&'#'));
+        if c == '"' && closes {
+            return Some(i + 1 + hashes);
+        }
+        i += 1;
+    }
+    Some(chars.len())
+}
+
+fn find_from(chars: &[char], from: usize, pred: impl Fn(char) -> bool) -> Option<usize> {
+    chars
+        .get(from..)?
+        .iter()
+        .position(|&c| pred(c))
+        .map(|p| p + from)
+}
+
+fn is_ident(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn prev_is_ident(chars: &[char], i: usize) -> bool {
+    i.checked_sub(1)
+        .and_then(|p| chars.get(p))
+        .is_some_and(|&c| is_ident(c))
+}
+
+fn is_word_start(chars: &[char], i: usize) -> bool {
+    chars.get(i).is_some_and(|&c| c.is_alphabetic() || c == '_') && !prev_is_ident(chars, i)
+}
+
+fn word_end(chars: &[char], i: usize) -> usize {
+    find_from(chars, i, |c| !is_ident(c)).unwrap_or(chars.len())
+}
+
+/// `fn name` declares an item; `fn(` is a function-pointer type.
+fn next_is_ident(chars: &[char], from: usize) -> bool {
+    find_from(chars, from, |c| !c.is_whitespace())
+        .and_then(|p| chars.get(p))
+        .is_some_and(|&c| c.is_alphabetic() || c == '_')
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,26 @@
+use super::*;
+
+#[test]
+fn apostrophe_in_jsx_text_does_not_swallow_the_file() {
+    let src = "function A() {\n  return <p>Don't do it</p>;\n}\n\nfunction D() {}\n";
+    let out = outline(src, Lang::Script);
+    assert!(out.contains("function A() { ... }"), "{out}");
+    assert!(out.contains("function D() { ... }"), "{out}");
+}
+
+#[test]
+fn script_strings_still_hide_braces() {
+    let src = "const a = '}';\nfunction f(x) { return \"{\"; }\nfunction g() {}\n";
+    let out = outline(src, Lang::Script);
+    assert!(out.contains("function f(x) { ... }"), "{out}");
+    assert!(out.contains("function g() { ... }"), "{out}");
+}
+
+#[test]
+fn rust_bodies_are_elided() {
+    let src = "/// Doc.\nfn a(s: &str) -> usize {\n    s.len()\n}\nstruct B;\n";
+    assert_eq!(
+        outline(src, Lang::Rust),
+        "/// Doc.\nfn a(s: &str) -> usize { ... }\nstruct B;\n"
+    );
+}
@@ -0,0 +1,34 @@
+mod braces;
+mod python;
+
+use std::path::Path;
+
+enum Syntax {
+    Braces(braces::Lang),
+    Python,
+}
+
+fn syntax(path: &Path) -> Option<Syntax> {
+    match path.extension().and_then(|e| e.to_str())? {
+        "rs" => Some(Syntax::Braces(braces::Lang::Rust)),
+        "ts" | "tsx" | "js" | "jsx" | "mjs" | "cjs" => Some(Syntax::Braces(braces::Lang::Script)),
+        "py" | "pyi" => Some(Syntax::Python),
+        _ => None,
+    }
+}
+
+/// Whether `outline` can shorten files with this path's extension.
+pub fn supports(path: &Path) -> bool {
+    syntax(path).is_some()
+}
+
+/// Signatures-only view of a source file: item declarations, type
+/// definitions and doc comments stay, function bodies become `{ ... }`
+/// (or `...` in Python). `None` when the language is not supported, in
+/// which case callers keep the full content.
+pub fn outline(path: &Path, content: &str) -> Option<String> {
+    Some(match syntax(path)? {
+        Syntax::Braces(lang) => braces::outline(content, lang),
+        Syntax::Python => python::outline(content),
+    })
+}
@@ -0,0 +1,159 @@
+/// Keep `def` signatures, decorators, classes and docstrings; replace each
+/// function body after its docstring with `...`.
+pub fn outline(src: &str) -> String {
+    let lines: Vec<&str> = src.lines().collect();
+    let mut out = String::with_capacity(src.len() / 2);
+    let mut i = 0;
+    while let Some(&line) = lines.get(i) {
+        let stripped = line.trim_start();
+        if !(stripped.starts_with("def ") || stripped.starts_with("async def ")) {
+            push_line(&mut out, line);
+            i += 1;
+            continue;
+        }
+        let indent = indent_of(line);
+        i = emit_signature(&lines, i, &mut out);
+        if !ends_with_colon(lines.get(i.saturating_sub(1)).copied().unwrap_or("")) {
+            // One-liner such as `def f(): return 1`; nothing to elide.
+            continue;
+        }
+        let body_end = block_end(&lines, i, indent);
+        let body_indent = lines
+            .get(i..body_end)
+            .unwrap_or_default()
+            .iter()
+            .find(|l| !l.trim().is_empty())
+            .map_or(indent + 4, |l| indent_of(l));
+        i = emit_docstring(&lines, i, body_end, &mut out);
+        push_line(&mut out, &format!("{}...", " ".repeat(body_indent)));
+        if body_end > i && lines.get(body_end - 1).is_some_and(|l| l.trim().is_empty()) {
+            out.push('\n');
+        }
+        i = body_end;
+    }
+    out
+}
+
+/// Emit the `def` line plus continuation lines until the parentheses
+/// close; returns the index of the first body line.
+fn emit_signature(lines: &[&str], start: usize, out: &mut String) -> usize {
+    let mut depth = 0i32;
+    let mut i = start;
+    while let Some(&line) = lines.get(i) {
+        push_line(out, line);
+        for c in code_part(line).chars() {
+            match c {
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' | '}' => depth -= 1,
+                _ => {}
+            }
+        }
+        i += 1;
+        if depth <= 0 {
+            break;
+        }
+    }
+    i
+}
+
+/// Keep a leading docstring of the block `[start, end)`.
+fn emit_docstring(lines: &[&str], start: usize, end: usize, out: &mut String) -> usize {
+    let Some(first) = (start..end).find(|&k| lines.get(k).is_some_and(|l| !l.trim().is_empty()))
+    else {
+        return start;
+    };
+    let text = lines.get(first).map_or("", |l| l.trim());
+    let Some(quote) = ["\"\"\"", "'''"].into_iter().find(|q| text.starts_with(q)) else {
+        return start;
+    };
+    let mut k = first;
+    let mut closed = text.len() >= 6 && text.ends_with(quote);
+    push_line(out, lines.get(k).copied().unwrap_or(""));
+    while !closed && k + 1 < end {
+        k += 1;
+        let line = lines.get(k).copied().unwrap_or("");
+        push_line(out, line);
+        closed = line.contains(quote);
+    }
+    k + 1
+}
+
+/// First line after `start` that is non-blank and indented at most
+/// `indent`, not counting lines inside a triple-quoted string.
+fn block_end(lines: &[&str], start: usize, indent: usize) -> usize {
+    let mut open: Option<&str> = None;
+    for (k, line) in lines.iter().enumerate().skip(start) {
+        if open.is_none() && !line.trim().is_empty() && indent_of(line) <= indent {
+            return k;
+        }
+        open = triple_quote_after(line, open);
+    }
+    lines.len()
+}
+
+/// The triple quote still open at the end of `line`, given the one open
+/// at its start.
+fn triple_quote_after<'q>(line: &str, mut open: Option<&'q str>) -> Option<&'q str> {
+    let mut rest = line;
+    loop {
+        let next = match open {
+            Some(q) => rest.find(q).map(|p| (p, q)),
+            None => ["\"\"\"", "\'\'\'"]
+                .into_iter()
+                .filter_map(|q| rest.find(q).map(|p| (p, q)))
+                .min(),
+        };
+        let Some((pos, quote)) = next else {
+            return open;
+        };
+        open = if open.is_some() { None } else { Some(quote) };
+        rest = rest.get(pos + quote.len()..).unwrap_or("");
+    }
+}
+
+fn ends_with_colon(line: &str) -> bool {
+    code_part(line).trim_end().ends_with(':')
+}
+
+/// The line without a trailing `#` comment (ignores `#` inside strings only roughly).
+fn code_part(line: &str) -> &str {
+    let mut quote: Option<char> = None;
+    for (idx, c) in line.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            (None, '#') => return line.get(..idx).unwrap_or(line),
+            _ => {}
+        }
+    }
+    line
+}
+
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+fn push_line(out: &mut String, line: &str) {
+    out.push_str(line);
+    out.push('\n');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn string_lines_at_column_zero_stay_in_the_body() {
+        let src = "def f():\n    x = \"\"\"\ntext at column 0\n\"\"\"\n    return x\n\ndef g():\n    pass\n";
+        assert_eq!(outline(src), "def f():\n    ...\n\ndef g():\n    ...\n");
+    }
+
+    #[test]
+    fn docstrings_and_classes_are_kept() {
+        let src = "class A:\n    def m(self):\n        \"\"\"Doc.\"\"\"\n        return 1\n";
+        assert_eq!(
+            outline(src),
+            "class A:\n    def m(self):\n        \"\"\"Doc.\"\"\"\n        ...\n"
+        );
+    }
+}
@@ -1,6 +1,9 @@
 use anyhow::Result;
 use arboard::Clipboard;
-use std::{fs, path::Path};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 
 pub fn to_clipboard(content: &str) -> Result<()> {
     let mut cb = Clipboard::new()?;
@@ -12,3 +15,31 @@ pub fn to_file(content: &str, path: &Path) -> Result<()> {
     fs::write(path, content)?;
     Ok(())
 }
+
+/// Write one file per part: `path` itself for a single part, otherwise
+/// `stem.part1.ext`, `stem.part2.ext`, … next to it.
+pub fn to_files(parts: &[String], path: &Path) -> Result<Vec<PathBuf>> {
+    let paths = part_paths(path, parts.len());
+    for (content, p) in parts.iter().zip(&paths) {
+        to_file(content, p)?;
+    }
+    Ok(paths)
+}
+
+fn part_paths(path: &Path, n: usize) -> Vec<PathBuf> {
+    if n <= 1 {
+        return vec![path.to_path_buf()];
+    }
+    let stem = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("export");
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| format!(".{e}"))
+        .unwrap_or_default();
+    (1..=n)
+        .map(|i| path.with_file_name(format!("{stem}.part{i}{ext}")))
+        .collect()
+}
@@ -1,37 +1,140 @@
-use crate::types::AppState;
-use anyhow::Result;
-use std::{fmt::Write as _, fs, path::Path};
+use crate::{
+    diff::{self, DiffMode},
+    format::{self, Document},
+    outline, split,
+    types::AppState,
+};
+use anyhow::{bail, Result};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 
-pub fn build_export(state: &AppState) -> Result<String> {
+/// The whole export as one document. With `strict`, the first unreadable
+/// file fails the export instead of becoming an error entry in it.
+pub fn build_export(state: &AppState, strict: bool) -> Result<String> {
+    let docs = collect(state, strict)?;
+    Ok(format::render(
+        state.format,
+        &project_name(&state.root),
+        &docs,
+    ))
+}
+
+/// The export cut into `part 1/N` … chunks of at most `state.part_limit`
+/// tokens each; a single part when no limit is set or everything fits.
+pub fn build_parts(state: &AppState, strict: bool) -> Result<Vec<String>> {
+    let Some(limit) = state.part_limit else {
+        return Ok(vec![build_export(state, strict)?]);
+    };
+    let docs = collect(state, strict)?;
     let name = project_name(&state.root);
-    let mut out = format!("// PAKR COMBINED TEXT EXPORT //\n// Project: {name}\n\n");
-    for path in state.selected_paths() {
-        let full = state.root.join(&path);
-        let display = path.display().to_string();
-        match fs::read_to_string(&full) {
-            Ok(content) => append_file(&mut out, &display, &content),
-            Err(e) => {
-                let _ = writeln!(out, "// ERROR: {display}: {e}");
-                out.push('\n');
+    let parts = split_numbered(state, &name, &docs, limit);
+    let total = parts.len();
+    Ok(parts
+        .iter()
+        .enumerate()
+        .map(|(i, docs)| {
+            let title = if total == 1 {
+                name.clone()
+            } else {
+                format!("{name} (part {}/{total})", i + 1)
+            };
+            format::render(state.format, &title, docs)
+        })
+        .collect())
+}
+
+/// Split with room for the `(part n/total)` title, sized first for two
+/// digits and again for more when the split turns out that long.
+fn split_numbered(
+    state: &AppState,
+    name: &str,
+    docs: &[Document],
+    limit: usize,
+) -> Vec<Vec<Document>> {
+    let count = |text: &str| state.tokens.count_text(text);
+    let mut digits = 2;
+    loop {
+        let nines = "9".repeat(digits);
+        let widest_title = format!("{name} (part {nines}/{nines})");
+        let measure = |docs: &[Document]| count(&format::render(state.format, &widest_title, docs));
+        let parts = split::into_parts(docs.to_vec(), limit, &count, &measure);
+        let needed = parts.len().to_string().len();
+        if needed <= digits {
+            return parts;
+        }
+        digits = needed;
+    }
+}
+
+fn collect(state: &AppState, strict: bool) -> Result<Vec<Document>> {
+    let paths = state.selected_paths();
+    let mut docs = if state.diff.mode == DiffMode::Only {
+        Vec::new()
+    } else {
+        contents(state, &paths, strict)?
+    };
+    if state.diff.mode != DiffMode::Off {
+        if let Some(doc) = diff::document(state, &paths) {
+            if let (true, Err(e)) = (strict, &doc.content) {
+                bail!("{}: {e}", doc.path);
             }
+            docs.push(doc);
         }
     }
-    Ok(out)
+    Ok(docs)
 }
 
-fn project_name(root: &Path) -> String {
+fn contents(state: &AppState, paths: &[PathBuf], strict: bool) -> Result<Vec<Document>> {
+    let mut docs = Vec::new();
+    for path in paths {
+        let full = state.root.join(path);
+        let display = path.display().to_string();
+        let content = fs::read_to_string(&full).map_err(|e| e.to_string());
+        if let (true, Err(e)) = (strict, &content) {
+            bail!("reading {display}: {e}");
+        }
+        let description = describe(state, path);
+        let outlined = match &content {
+            Ok(text) if state.wants_outline(path) => outline::outline(path, text),
+            _ => None,
+        };
+        docs.push(match outlined {
+            Some(text) => Document {
+                path: display,
+                note: Some("outline".to_string()),
+                tokens: state.tokens.count_text(&text),
+                content: Ok(text),
+                lang: None,
+                description,
+            },
+            None => Document {
+                path: display,
+                note: None,
+                content,
+                tokens: state.file_tokens(path).unwrap_or(0),
+                lang: None,
+                description,
+            },
+        });
+    }
+    Ok(docs)
+}
+
+/// The SEMMAP note for `path` as a one-line header, when descriptions are on.
+fn describe(state: &AppState, path: &Path) -> Option<String> {
+    let note = state
+        .graph
+        .as_ref()?
+        .note(path)
+        .filter(|_| state.describe)?;
+    Some(note.summary()).filter(|s| !s.is_empty())
+}
+
+pub fn project_name(root: &Path) -> String {
     root.file_name()
         .and_then(|n| n.to_str())
         .unwrap_or("project")
         .to_string()
 }
-
-fn append_file(out: &mut String, path: &str, content: &str) {
-    let _ = writeln!(out, "// ===== START: {path} =====");
-    out.push_str(content);
-    if !content.ends_with('\n') {
-        out.push('\n');
-    }
-    let _ = writeln!(out, "// ===== END: {path} =====");
-    out.push('\n');
-}
@@ -0,0 +1,54 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+/// Files and folders where a folder entry stands for everything below it.
+#[derive(Debug, Clone, Default)]
+pub struct PathList(Vec<PathBuf>);
+
+impl PathList {
+    pub fn toggle(&mut self, path: PathBuf) {
+        if let Some(pos) = self.0.iter().position(|p| p == &path) {
+            self.0.remove(pos);
+        } else {
+            self.0.push(path);
+        }
+    }
+    pub fn insert(&mut self, path: PathBuf) {
+        if !self.0.contains(&path) {
+            self.0.push(path);
+        }
+    }
+    /// [`Self::insert`] for many paths, without a scan of the list per path.
+    pub fn extend(&mut self, paths: impl IntoIterator<Item = PathBuf>) {
+        let mut seen: HashSet<PathBuf> = self.0.iter().cloned().collect();
+        for path in paths {
+            if seen.insert(path.clone()) {
+                self.0.push(path);
+            }
+        }
+    }
+    /// Drops every entry at or below `path`.
+    pub fn remove_under(&mut self, path: &Path) {
+        self.0.retain(|p| !p.starts_with(path));
+    }
+    /// True when `path` or one of its ancestor folders is listed.
+    pub fn contains(&self, path: &Path) -> bool {
+        self.0.iter().any(|p| path.starts_with(p))
+    }
+    /// Listed folders strictly above `path`.
+    pub fn ancestors_of(&self, path: &Path) -> Vec<PathBuf> {
+        self.0
+            .iter()
+            .filter(|p| path.starts_with(p) && p.as_path() != path)
+            .cloned()
+            .collect()
+    }
+    pub fn items(&self) -> &[PathBuf] {
+        &self.0
+    }
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
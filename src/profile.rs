@@ -0,0 +1,65 @@
+use crate::{snapshot::Snapshot, types::AppState};
+use anyhow::{bail, Context, Result};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Profiles live in `<root>/.pakr/profiles/<name>.toml`.
+pub fn dir(root: &Path) -> PathBuf {
+    root.join(".pakr").join("profiles")
+}
+
+/// Saved profile names, sorted.
+pub fn list(root: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(dir(root)) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .filter_map(std::result::Result::ok)
+        .filter_map(|e| {
+            let path = e.path();
+            let is_toml = path.extension().is_some_and(|x| x == "toml");
+            is_toml
+                .then(|| path.file_stem()?.to_str().map(str::to_string))
+                .flatten()
+        })
+        .collect();
+    names.sort();
+    names
+}
+
+pub fn save(state: &AppState, name: &str) -> Result<PathBuf> {
+    let path = path_for(&state.root, name)?;
+    let text = toml::to_string_pretty(&Snapshot::capture(state))?;
+    fs::create_dir_all(dir(&state.root))?;
+    fs::write(&path, text).with_context(|| format!("writing {}", path.display()))?;
+    Ok(path)
+}
+
+/// Apply profile `name`; returns the saved paths that no longer exist.
+pub fn load(state: &mut AppState, name: &str) -> Result<Vec<PathBuf>> {
+    let path = path_for(&state.root, name)?;
+    let text = fs::read_to_string(&path)
+        .with_context(|| format!("no profile '{name}' ({})", path.display()))?;
+    let snapshot: Snapshot =
+        toml::from_str(&text).with_context(|| format!("parsing {}", path.display()))?;
+    Ok(snapshot.apply(state))
+}
+
+pub fn delete(root: &Path, name: &str) -> Result<()> {
+    let path = path_for(root, name)?;
+    fs::remove_file(&path).with_context(|| format!("removing {}", path.display()))
+}
+
+fn path_for(root: &Path, name: &str) -> Result<PathBuf> {
+    let valid = !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !valid {
+        bail!("invalid profile name '{name}' (use letters, digits, '-', '_' and '.')");
+    }
+    Ok(dir(root).join(format!("{name}.toml")))
+}
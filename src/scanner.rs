@@ -1,28 +1,24 @@
-use crate::types::FileNode;
+use crate::{
+    config::{self, Config},
+    types::FileNode,
+};
 use anyhow::Result;
+use ignore::WalkBuilder;
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     ffi::OsStr,
-    fs,
     path::{Path, PathBuf},
 };
 
-const IGNORE_DIRS: &[&str] = &[
-    ".git",
-    "node_modules",
-    "target",
-    "dist",
-    "build",
-    ".next",
-    ".nuxt",
-    "__pycache__",
-    "coverage",
-    ".cache",
-];
+/// Dotfiles that are still worth packing.
+const KEEP_DOTFILES: &[&str] = &[".gitignore", ".ignore", ".pakrignore", ".slopchopignore"];
 
 pub struct ScanResult {
     pub tree: FileNode,
     pub file_sizes: HashMap<PathBuf, u64>,
+    /// Files hidden by `.gitignore` (at any depth), `.ignore`,
+    /// `.git/info/exclude` or the global git excludes file.
+    pub ignored: HashSet<PathBuf>,
 }
 
 #[derive(Debug, Clone)]
@@ -34,62 +30,119 @@ pub struct FlatItem {
     pub depth: usize,
 }
 
-pub fn scan(root: &Path) -> Result<ScanResult> {
-    let mut file_sizes = HashMap::new();
-    let tree = walk(root, root, &mut file_sizes)?;
-    Ok(ScanResult { tree, file_sizes })
-}
+/// Walk `root` twice: once unfiltered to build the tree (so the gitignore
+/// toggle can bring ignored files back), once with every ignore source on
+/// to learn which of those files are ignored. `.pakrignore` and the
+/// configured `ignore_dirs` apply to both walks.
+pub fn scan(root: &Path, config: &Config) -> Result<ScanResult> {
+    let ignore_dirs = config.ignore_dirs();
+    let mut sizes = HashMap::new();
+    let mut children: HashMap<PathBuf, Vec<(PathBuf, bool)>> = HashMap::new();
+    for entry in walker(root, false, &ignore_dirs)
+        .build()
+        .filter_map(std::result::Result::ok)
+    {
+        let rel = relative_path(root, entry.path());
+        let Some(parent) = rel.parent().map(Path::to_path_buf) else {
+            continue;
+        };
+        let Ok(meta) = entry.metadata() else {
+            continue;
+        };
+        if meta.is_file() {
+            sizes.insert(rel.clone(), meta.len());
+        }
+        children
+            .entry(parent)
+            .or_default()
+            .push((rel, meta.is_dir()));
+    }
 
-fn walk(root: &Path, path: &Path, sizes: &mut HashMap<PathBuf, u64>) -> Result<FileNode> {
-    let name = path
+    let kept: HashSet<PathBuf> = walker(root, true, &ignore_dirs)
+        .build()
+        .filter_map(std::result::Result::ok)
+        .map(|e| relative_path(root, e.path()))
+        .collect();
+    let ignored = sizes
+        .keys()
+        .filter(|p| !kept.contains(*p))
+        .cloned()
+        .collect();
+
+    let name = root
         .file_name()
         .and_then(OsStr::to_str)
         .unwrap_or(".")
         .to_string();
-    let meta = fs::metadata(path)?;
-
-    if meta.is_file() {
-        let size = meta.len();
-        let rel = relative_path(root, path);
-        sizes.insert(rel.clone(), size);
-        return Ok(FileNode {
-            path: rel,
-            name,
-            is_dir: false,
-            size,
-            children: Vec::new(),
-            expanded: false,
-        });
+    let tree = build_node(PathBuf::new(), name, true, &children, &sizes);
+    Ok(ScanResult {
+        tree,
+        file_sizes: sizes,
+        ignored,
+    })
+}
+
+fn walker(root: &Path, filtered: bool, ignore_dirs: &[String]) -> WalkBuilder {
+    let mut builder = WalkBuilder::new(root);
+    if let Some(path) = config::user_ignore_file() {
+        builder.add_ignore(path);
     }
+    let ignore_dirs = ignore_dirs.to_vec();
+    builder
+        .add_custom_ignore_filename(config::IGNORE_FILE)
+        .standard_filters(filtered)
+        .hidden(false)
+        .require_git(false)
+        .follow_links(true)
+        .filter_entry(move |e| {
+            let name = e.file_name().to_string_lossy();
+            e.depth() == 0
+                || !(ignore_dirs.iter().any(|d| *d == name)
+                    || name.starts_with('.') && !KEEP_DOTFILES.contains(&name.as_ref()))
+        });
+    builder
+}
 
-    let mut children: Vec<FileNode> = fs::read_dir(path)?
-        .filter_map(std::result::Result::ok)
-        .filter(|e| {
-            let s = e.file_name().to_string_lossy().into_owned();
-            !s.starts_with('.') || s == ".gitignore" || s == ".slopchopignore"
-        })
-        .filter(|e| {
-            let s = e.file_name().to_string_lossy().into_owned();
-            !IGNORE_DIRS.contains(&s.as_str())
-        })
-        .filter_map(|e| walk(root, &e.path(), sizes).ok())
-        .collect();
+fn build_node(
+    path: PathBuf,
+    name: String,
+    is_dir: bool,
+    children: &HashMap<PathBuf, Vec<(PathBuf, bool)>>,
+    sizes: &HashMap<PathBuf, u64>,
+) -> FileNode {
+    let mut nodes: Vec<FileNode> = if is_dir {
+        children
+            .get(&path)
+            .map(|kids| {
+                kids.iter()
+                    .map(|(p, dir)| {
+                        let name = p
+                            .file_name()
+                            .map(|n| n.to_string_lossy().into_owned())
+                            .unwrap_or_default();
+                        build_node(p.clone(), name, *dir, children, sizes)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    } else {
+        Vec::new()
+    };
 
-    children.sort_by(|a, b| match (a.is_dir, b.is_dir) {
+    nodes.sort_by(|a, b| match (a.is_dir, b.is_dir) {
         (true, false) => std::cmp::Ordering::Less,
         (false, true) => std::cmp::Ordering::Greater,
         _ => a.name.cmp(&b.name),
     });
 
-    let rel = relative_path(root, path);
-    Ok(FileNode {
-        path: rel,
+    FileNode {
+        size: sizes.get(&path).copied().unwrap_or(0),
+        path,
         name,
-        is_dir: true,
-        size: 0,
-        children,
-        expanded: true,
-    })
+        is_dir,
+        children: nodes,
+        expanded: is_dir,
+    }
 }
 
 fn relative_path(root: &Path, path: &Path) -> PathBuf {
@@ -133,6 +186,52 @@ fn push_flat(node: &FileNode, depth: usize, out: &mut Vec<FlatItem>) {
     }
 }
 
+/// Rows whose item satisfies `hit`, plus the folders leading to them. Collapsed
+/// folders are looked into as well, so a match is never hidden.
+pub fn flatten_matching(node: &FileNode, hit: &dyn Fn(&FlatItem) -> bool) -> Vec<FlatItem> {
+    let mut out = Vec::new();
+    push_matching(node, 0, hit, &mut out);
+    out
+}
+
+fn push_matching(
+    node: &FileNode,
+    depth: usize,
+    hit: &dyn Fn(&FlatItem) -> bool,
+    out: &mut Vec<FlatItem>,
+) -> bool {
+    let item = FlatItem {
+        path: node.path.clone(),
+        name: node.name.clone(),
+        is_dir: node.is_dir,
+        expanded: node.is_dir,
+        depth,
+    };
+    let is_hit = hit(&item);
+    let at = out.len();
+    out.push(item);
+    let mut any = false;
+    for child in &node.children {
+        any |= push_matching(child, depth + 1, hit, out);
+    }
+    if !is_hit && !any && depth > 0 {
+        out.truncate(at);
+        return false;
+    }
+    true
+}
+
+/// Expand every folder above `path` so it shows up in the visible tree.
+pub fn reveal(node: &mut FileNode, path: &Path) {
+    if !node.is_dir || !path.starts_with(&node.path) || node.path.as_path() == path {
+        return;
+    }
+    node.expanded = true;
+    for child in &mut node.children {
+        reveal(child, path);
+    }
+}
+
 pub fn toggle_node_expanded(tree: &mut FileNode, path: &Path) {
     if tree.path.as_path() == path {
         tree.expanded = !tree.expanded;
@@ -160,3 +259,13 @@ pub fn expand_all(node: &mut FileNode) {
         }
     }
 }
+
+pub fn find_node<'a>(node: &'a FileNode, path: &Path) -> Option<&'a FileNode> {
+    if node.path.as_path() == path {
+        return Some(node);
+    }
+    if !node.is_dir || !path.starts_with(&node.path) {
+        return None;
+    }
+    node.children.iter().find_map(|c| find_node(c, path))
+}
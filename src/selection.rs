@@ -0,0 +1,257 @@
+use crate::{
+    scanner,
+    types::{AppState, ExtMode, FileNode},
+};
+use std::{
+    collections::{HashMap, HashSet},
+    hash::BuildHasher,
+    path::{Path, PathBuf},
+};
+
+impl AppState {
+    pub fn are_all_selected(&self) -> bool {
+        self.categories.iter().all(|c| c.enabled)
+    }
+
+    pub fn selected_paths(&self) -> Vec<PathBuf> {
+        let mut result: Vec<PathBuf> = self
+            .categories
+            .iter()
+            .filter(|c| c.enabled)
+            .flat_map(|c| c.files.iter().cloned())
+            .collect();
+        if !self.include.is_empty() {
+            let included = self.file_sizes.keys().filter(|p| self.include.contains(p));
+            result.extend(included.cloned());
+        }
+
+        if !self.subsystems.is_empty() {
+            let sub_set: HashSet<&PathBuf> = self
+                .subsystems
+                .iter()
+                .filter(|s| s.enabled)
+                .flat_map(|s| s.files.iter())
+                .collect();
+            result.retain(|p| sub_set.contains(p));
+        }
+
+        if let (Some(tag), Some(graph)) = (&self.tag_filter, &self.graph) {
+            let tagged = graph.tagged(tag);
+            result.retain(|p| tagged.contains(p));
+        }
+
+        self.apply_extensions(&mut result);
+
+        result.retain(|p| !self.exclude.contains(p.as_path()));
+
+        if self.respect_gitignore && self.gitignore_filter.has_filter() {
+            result.retain(|p| !self.gitignore_filter.is_ignored(p));
+        }
+
+        for path in &self.manual_includes {
+            if !self.exclude.contains(path.as_path()) {
+                result.push(path.clone());
+            }
+        }
+
+        result.sort();
+        result.dedup();
+        result
+    }
+
+    /// Entries of the ADDED list: checked files and folders, then files
+    /// picked by name.
+    pub fn added(&self) -> impl Iterator<Item = &PathBuf> {
+        self.include.items().iter().chain(&self.manual_includes)
+    }
+
+    fn apply_extensions(&self, result: &mut Vec<PathBuf>) {
+        let mut dropped: HashSet<&PathBuf> = HashSet::new();
+        for group in &self.extensions {
+            match group.mode {
+                ExtMode::Inherit => {}
+                ExtMode::Include => result.extend(group.files.iter().cloned()),
+                ExtMode::Exclude => dropped.extend(group.files.iter()),
+            }
+        }
+        if !dropped.is_empty() {
+            result.retain(|p| !dropped.contains(p));
+        }
+    }
+
+    pub fn total_tokens(&self) -> usize {
+        self.tokens_for(&self.selected_paths())
+    }
+
+    pub fn total_bytes(&self) -> u64 {
+        self.selected_paths()
+            .iter()
+            .filter_map(|p| self.file_sizes.get(p))
+            .sum()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckState {
+    Checked,
+    Partial,
+    Unchecked,
+}
+
+impl CheckState {
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Self::Checked => "✓",
+            Self::Partial => "◐",
+            Self::Unchecked => " ",
+        }
+    }
+}
+
+/// Check state of every directory, derived from its selectable descendants.
+pub fn dir_states<S: BuildHasher>(
+    state: &AppState,
+    selected: &HashSet<PathBuf, S>,
+) -> HashMap<PathBuf, CheckState> {
+    let mut out = HashMap::new();
+    dir_counts(&state.tree, state, selected, &mut out);
+    out
+}
+
+fn dir_counts<S: BuildHasher>(
+    node: &FileNode,
+    state: &AppState,
+    selected: &HashSet<PathBuf, S>,
+    out: &mut HashMap<PathBuf, CheckState>,
+) -> (usize, usize) {
+    if !node.is_dir {
+        if !is_selectable(state, &node.path) {
+            return (0, 0);
+        }
+        return (usize::from(selected.contains(&node.path)), 1);
+    }
+    let (mut on, mut total) = (0, 0);
+    for child in &node.children {
+        let (c_on, c_total) = dir_counts(child, state, selected, out);
+        on += c_on;
+        total += c_total;
+    }
+    let check = if on == 0 {
+        CheckState::Unchecked
+    } else if on == total {
+        CheckState::Checked
+    } else {
+        CheckState::Partial
+    };
+    out.insert(node.path.clone(), check);
+    (on, total)
+}
+
+/// Files hidden by an active gitignore filter never count toward a folder's state.
+fn is_selectable(state: &AppState, path: &Path) -> bool {
+    !(state.respect_gitignore && state.gitignore_filter.is_ignored(path))
+}
+
+/// Toggle a file or a whole subtree. Checked and partial nodes are dropped,
+/// unchecked nodes are pulled in together with every descendant.
+pub fn toggle(state: &mut AppState, path: &Path) {
+    let selected: HashSet<PathBuf> = state.selected_paths().into_iter().collect();
+    let Some(node) = scanner::find_node(&state.tree, path) else {
+        return;
+    };
+    let check = if node.is_dir {
+        dir_states(state, &selected)
+            .get(path)
+            .copied()
+            .unwrap_or(CheckState::Unchecked)
+    } else if selected.contains(path) {
+        CheckState::Checked
+    } else {
+        CheckState::Unchecked
+    };
+    if check == CheckState::Unchecked {
+        select(state, path);
+    } else {
+        deselect(state, path);
+    }
+}
+
+/// Pull in one file picked by name. Unlike [`toggle`], the file is added
+/// even when the gitignore filter would hide it, since the user asked for
+/// it explicitly.
+pub fn add_file(state: &mut AppState, path: &Path) {
+    add_files(state, &[path.to_path_buf()]);
+}
+
+/// [`add_file`] for a batch, such as a dependency closure, computing the
+/// selection once for all of them.
+pub fn add_files(state: &mut AppState, paths: &[PathBuf]) {
+    for path in paths {
+        state.exclude.remove_under(path);
+        for ancestor in state.exclude.ancestors_of(path) {
+            split_exclusion(state, &ancestor, path);
+        }
+    }
+    let selected: HashSet<PathBuf> = state.selected_paths().into_iter().collect();
+    let mut added: HashSet<PathBuf> = state.manual_includes.iter().cloned().collect();
+    for path in paths {
+        if !selected.contains(path) && added.insert(path.clone()) {
+            state.manual_includes.push(path.clone());
+        }
+    }
+}
+
+fn deselect(state: &mut AppState, path: &Path) {
+    state.manual_includes.retain(|p| !p.starts_with(path));
+    state.include.remove_under(path);
+    state.exclude.remove_under(path);
+    state.exclude.insert(path.to_path_buf());
+}
+
+/// Lift exclusions over `path`, then list it as an include when that alone
+/// leaves some of its files out. Files a subsystem, tag or type filter
+/// still drops are added by name, so the check always takes effect.
+fn select(state: &mut AppState, path: &Path) {
+    state.exclude.remove_under(path);
+    for ancestor in state.exclude.ancestors_of(path) {
+        split_exclusion(state, &ancestor, path);
+    }
+    let selected: HashSet<PathBuf> = state.selected_paths().into_iter().collect();
+    let files = scanner::find_node(&state.tree, path)
+        .map(scanner::all_files)
+        .unwrap_or_default();
+    if files
+        .iter()
+        .any(|p| !selected.contains(p) && is_selectable(state, p))
+    {
+        state.include.remove_under(path);
+        state.include.insert(path.to_path_buf());
+    }
+    let selected: HashSet<PathBuf> = state.selected_paths().into_iter().collect();
+    let blocked: Vec<PathBuf> = files
+        .into_iter()
+        .filter(|p| !selected.contains(p) && is_selectable(state, p))
+        .collect();
+    add_files(state, &blocked);
+}
+
+/// Replace an excluded ancestor folder with exclusions of every sibling
+/// along the way down to `keep`, so only `keep` becomes reachable again.
+fn split_exclusion(state: &mut AppState, ancestor: &Path, keep: &Path) {
+    state.exclude.toggle(ancestor.to_path_buf());
+    let mut current = scanner::find_node(&state.tree, ancestor);
+    while let Some(node) = current.filter(|n| n.path.as_path() != keep) {
+        let mut next = None;
+        for child in &node.children {
+            if keep.starts_with(&child.path) {
+                next = Some(child);
+            } else {
+                state.exclude.insert(child.path.clone());
+            }
+        }
+        current = next;
+    }
+}
+
+#[cfg(test)]
+mod tests;
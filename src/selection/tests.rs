@@ -0,0 +1,33 @@
+use super::*;
+use anyhow::Result;
+use std::fs;
+
+#[test]
+fn checking_a_file_of_a_disabled_subsystem_selects_it() -> Result<()> {
+    let root = std::env::temp_dir().join(format!("pakr-selection-{}", std::process::id()));
+    let files = [
+        ("Cargo.toml", "[package]\nname = \"demo\"\n"),
+        ("src/main.rs", "mod a;\nfn main() { a::run(); }\n"),
+        ("src/a.rs", "pub fn run() {}\n"),
+    ];
+    for (path, text) in files {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap_or(&root))?;
+        fs::write(path, text)?;
+    }
+    let state = crate::app::load(&root);
+    fs::remove_dir_all(&root)?;
+    let mut state = state?;
+    for sub in &mut state.subsystems {
+        sub.enabled = false;
+    }
+    let (main, file) = (PathBuf::from("src/main.rs"), PathBuf::from("src/a.rs"));
+    assert!(state.selected_paths().is_empty());
+    toggle(&mut state, Path::new("src"));
+    assert!(state.selected_paths().contains(&main));
+    toggle(&mut state, Path::new("src"));
+    assert!(!state.selected_paths().contains(&main));
+    toggle(&mut state, &file);
+    assert_eq!(state.selected_paths(), vec![file]);
+    Ok(())
+}
@@ -0,0 +1,192 @@
+use super::{listed_path, mermaid, parse_all, parse_layer_header, sources, Node, NodeMap};
+use crate::{
+    categories,
+    config::{self, Bucket, Config},
+    scanner::{self, ScanResult},
+};
+use anyhow::{bail, Result};
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+/// Something in a map file that parsing would skip or that no longer
+/// matches the tree. `line` is 1-based; files missing from the map have
+/// none and are reported against the first map file.
+#[derive(Debug, Clone)]
+pub struct Issue {
+    pub file: PathBuf,
+    pub line: Option<usize>,
+    pub kind: IssueKind,
+}
+
+#[derive(Debug, Clone)]
+pub enum IssueKind {
+    /// A `## Layer` heading without a number and ` -- ` label.
+    BadHeader(String),
+    /// Listed in a layer but not on disk.
+    Missing(PathBuf),
+    /// Listed again after the entry at `first` (map file and line).
+    Duplicate {
+        path: PathBuf,
+        first: (PathBuf, usize),
+    },
+    /// An edge end that names no listed file.
+    UnknownNode(String),
+    /// An edge end whose flattened id fits several listed files.
+    AmbiguousNode { id: String, paths: Vec<PathBuf> },
+    /// A source file on disk that no layer lists.
+    Unlisted(PathBuf),
+}
+
+impl Issue {
+    pub fn message(&self) -> String {
+        match &self.kind {
+            IssueKind::BadHeader(text) => {
+                format!("unreadable layer header `{text}` (want `## Layer N -- Label`)")
+            }
+            IssueKind::Missing(path) => format!("{} is listed but not on disk", path.display()),
+            IssueKind::Duplicate { path, first } => format!(
+                "{} is already listed at {}:{}",
+                path.display(),
+                first.0.display(),
+                first.1
+            ),
+            IssueKind::UnknownNode(id) => format!("edge names unknown node `{id}`"),
+            IssueKind::AmbiguousNode { id, paths } => format!(
+                "node `{id}` could be any of {}; declare it as `{id}[\"path\"]`",
+                paths
+                    .iter()
+                    .map(|p| p.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            IssueKind::Unlisted(path) => format!("{} is in no layer", path.display()),
+        }
+    }
+
+    /// `SEMMAP.md:12: message`, the way compilers point at a line.
+    pub fn render(&self) -> String {
+        match self.line {
+            Some(line) => format!("{}:{line}: {}", self.file.display(), self.message()),
+            None => format!("{}: {}", self.file.display(), self.message()),
+        }
+    }
+}
+
+/// Check the map files of `root` against a fresh scan.
+pub fn run(root: &Path) -> Result<Vec<Issue>> {
+    let config = config::load(root)?;
+    let scan = scanner::scan(root, &config)?;
+    let maps = sources::read(root, &config, &scanner::all_files(&scan.tree))?;
+    if maps.is_empty() {
+        bail!("no SEMMAP found; `pakr semmap init` writes one");
+    }
+    Ok(check(&maps, &scan, &config))
+}
+
+/// Every problem in `maps`, file by file in line order, followed by the
+/// source files (gitignored ones aside) that no layer mentions.
+pub fn check(maps: &[(PathBuf, String)], scan: &ScanResult, config: &Config) -> Vec<Issue> {
+    let on_disk: HashSet<PathBuf> = scanner::all_files(&scan.tree).into_iter().collect();
+    let listed_files = parse_all(maps).map_or_else(Vec::new, |d| d.all_files());
+    let nodes = NodeMap::new(&listed_files);
+    let mut listed: HashMap<PathBuf, (PathBuf, usize)> = HashMap::new();
+    let mut issues = Vec::new();
+    for (file, text) in maps {
+        let mut found = entries(file, text, &on_disk, &mut listed);
+        found.extend(node_issues(file, text, &nodes));
+        found.sort_by_key(|i| i.line);
+        issues.extend(found);
+    }
+    let Some((first, _)) = maps.first() else {
+        return issues;
+    };
+    let mut unlisted: Vec<&PathBuf> = on_disk
+        .iter()
+        .filter(|p| !listed.contains_key(*p) && !scan.ignored.contains(*p))
+        .filter(|p| categories::in_bucket(p, Bucket::Source, config))
+        .collect();
+    unlisted.sort();
+    issues.extend(unlisted.into_iter().map(|p| Issue {
+        file: first.clone(),
+        line: None,
+        kind: IssueKind::Unlisted(p.clone()),
+    }));
+    issues
+}
+
+/// Layer headers and file entries of one map file. `listed` carries the
+/// entries seen so far, across files, to catch duplicates.
+fn entries(
+    file: &Path,
+    text: &str,
+    on_disk: &HashSet<PathBuf>,
+    listed: &mut HashMap<PathBuf, (PathBuf, usize)>,
+) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    let mut in_layer = false;
+    for (n, line) in text.lines().enumerate() {
+        let t = line.trim();
+        let kind = if t.starts_with("## Layer") {
+            in_layer = t
+                .strip_prefix("## Layer ")
+                .and_then(parse_layer_header)
+                .is_some();
+            if in_layer {
+                continue;
+            }
+            IssueKind::BadHeader(t.to_string())
+        } else if let Some(path) = listed_path(t).filter(|_| in_layer) {
+            let path = PathBuf::from(path);
+            if let Some(first) = listed.get(&path) {
+                IssueKind::Duplicate {
+                    path,
+                    first: first.clone(),
+                }
+            } else {
+                listed.insert(path.clone(), (file.to_path_buf(), n + 1));
+                if on_disk.contains(&path) {
+                    continue;
+                }
+                IssueKind::Missing(path)
+            }
+        } else {
+            continue;
+        };
+        issues.push(Issue {
+            file: file.to_path_buf(),
+            line: Some(n + 1),
+            kind,
+        });
+    }
+    issues
+}
+
+/// Edge ends in the file's Mermaid graph that resolve to no single listed
+/// file, so the edge is dropped.
+fn node_issues(file: &Path, text: &str, nodes: &NodeMap) -> Vec<Issue> {
+    let chart = mermaid::parse(text);
+    let mut seen = HashSet::new();
+    let mut issues = Vec::new();
+    for edge in &chart.edges {
+        for id in [&edge.from, &edge.to] {
+            let kind = match nodes.resolve(id, &chart) {
+                Node::File(_) => continue,
+                Node::Unknown => IssueKind::UnknownNode(id.clone()),
+                Node::Ambiguous(paths) => IssueKind::AmbiguousNode {
+                    id: id.clone(),
+                    paths: paths.into_iter().map(Path::to_path_buf).collect(),
+                },
+            };
+            if seen.insert((edge.line, id)) {
+                issues.push(Issue {
+                    file: file.to_path_buf(),
+                    line: Some(edge.line),
+                    kind,
+                });
+            }
+        }
+    }
+    issues
+}
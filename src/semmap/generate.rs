@@ -0,0 +1,153 @@
+use super::{layers, node_id, notes, parse_all, sources, DepEdge, SEMMAP_FILE};
+use crate::{
+    analyzer,
+    config::{self, Config},
+    scanner,
+};
+use anyhow::Result;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt::Write,
+    path::{Path, PathBuf},
+};
+
+/// A freshly generated SEMMAP.md and what went into it.
+pub struct Generated {
+    /// Where to write it, relative to the root: the one map file it was
+    /// read from, else the first plain configured path. `None` when the map
+    /// is split over several files or only globs are configured.
+    pub path: Option<PathBuf>,
+    /// The existing map files it was built over.
+    pub sources: Vec<PathBuf>,
+    pub text: String,
+    pub files: usize,
+    pub edges: usize,
+}
+
+/// Build a SEMMAP.md for `root` from its import graph. Descriptions under
+/// each file in the existing map files are carried over, as are the title
+/// and the `## Legend` section.
+pub fn generate(root: &Path) -> Result<Generated> {
+    let config = config::load(root)?;
+    let scan = scanner::scan(root, &config)?;
+    let files: Vec<PathBuf> = scanner::all_files(&scan.tree)
+        .into_iter()
+        .filter(|p| !scan.ignored.contains(p))
+        .collect();
+    let edges = analyzer::analyze(root, &files).map_or_else(Vec::new, |g| g.edges);
+
+    let existing = sources::read(root, &config, &scanner::all_files(&scan.tree))?;
+    let notes: HashMap<PathBuf, Vec<String>> = existing
+        .iter()
+        .flat_map(|(_, text)| notes::descriptions(text))
+        .collect();
+    let legend = existing
+        .iter()
+        .map(|(_, text)| notes::legend_lines(text))
+        .find(|lines| !lines.is_empty())
+        .unwrap_or_default();
+    let name = parse_all(&existing)
+        .map(|d| d.project_name)
+        .filter(|n| !n.is_empty())
+        .or_else(|| root.file_name().map(|n| n.to_string_lossy().into_owned()))
+        .unwrap_or_default();
+
+    let layers = layers::assign(&files, &edges, &config);
+    let placed: HashSet<&PathBuf> = layers.iter().flatten().collect();
+    let edges: Vec<&DepEdge> = edges
+        .iter()
+        .filter(|e| placed.contains(&e.from) && placed.contains(&e.to))
+        .collect();
+    Ok(Generated {
+        path: output_path(&config, &existing),
+        sources: existing.into_iter().map(|(p, _)| p).collect(),
+        text: render(&name, &legend, &layers, &edges, &notes),
+        files: placed.len(),
+        edges: edges.len(),
+    })
+}
+
+/// The map file to replace: the only existing one, else the first plain
+/// path in the configured `semmap` list, else SEMMAP.md.
+fn output_path(config: &Config, existing: &[(PathBuf, String)]) -> Option<PathBuf> {
+    match existing {
+        [(path, _)] => Some(path.clone()),
+        [] => match &config.semmap {
+            Some(patterns) => patterns
+                .iter()
+                .find(|p| !p.contains(['*', '?', '[', '{']))
+                .map(PathBuf::from),
+            None => Some(PathBuf::from(SEMMAP_FILE)),
+        },
+        _ => None,
+    }
+}
+
+/// Node id per file: the flattened path, numbered and declared with its
+/// path as label where two files flatten alike (`a-b.rs`, `a_b.rs`).
+fn node_ids<'a>(edges: &[&'a DepEdge]) -> BTreeMap<&'a PathBuf, String> {
+    let mut by_id: HashMap<String, Vec<&PathBuf>> = HashMap::new();
+    for path in edges.iter().flat_map(|e| [&e.from, &e.to]) {
+        let paths = by_id.entry(node_id(path)).or_default();
+        if !paths.contains(&path) {
+            paths.push(path);
+        }
+    }
+    let mut ids = BTreeMap::new();
+    for (id, mut paths) in by_id {
+        paths.sort();
+        if let [path] = paths.as_slice() {
+            ids.insert(*path, id);
+            continue;
+        }
+        for (n, path) in paths.into_iter().enumerate() {
+            ids.insert(path, format!("{id}_{}", n + 1));
+        }
+    }
+    ids
+}
+
+fn render(
+    name: &str,
+    legend: &[String],
+    layers: &[Vec<PathBuf>; 5],
+    edges: &[&DepEdge],
+    notes: &HashMap<PathBuf, Vec<String>>,
+) -> String {
+    let mut out = format!("# project -- {name}\n");
+    if !legend.is_empty() {
+        out.push_str("\n## Legend\n\n");
+        for line in legend {
+            let _ = writeln!(out, "{line}");
+        }
+    }
+    for (index, (label, files)) in layers::LAYERS.iter().zip(layers).enumerate() {
+        if files.is_empty() {
+            continue;
+        }
+        let _ = write!(out, "\n## Layer {index} -- {label}\n");
+        for file in files {
+            let _ = write!(out, "\n`{}`\n", file.display());
+            for line in notes.get(file).into_iter().flatten() {
+                let _ = writeln!(out, "{line}");
+            }
+        }
+    }
+    if !edges.is_empty() {
+        out.push_str("\n## Dependencies\n\n```mermaid\ngraph TD\n");
+        let ids = node_ids(edges);
+        for (path, id) in &ids {
+            if *id != node_id(path) {
+                let _ = writeln!(out, "    {id}[\"{}\"]", path.display());
+            }
+        }
+        for edge in edges {
+            let (Some(from), Some(to)) = (ids.get(&edge.from), ids.get(&edge.to)) else {
+                continue;
+            };
+            let _ = writeln!(out, "    {from} --> {to}");
+        }
+        out.push_str("```\n");
+    }
+    out
+}
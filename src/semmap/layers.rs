@@ -0,0 +1,158 @@
+use super::DepEdge;
+use crate::{
+    categories,
+    config::{Bucket, Config},
+};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    path::{Path, PathBuf},
+};
+
+/// Layers written by `pakr semmap init`, by index. Config, Entry and Core
+/// are on by default.
+pub const LAYERS: [&str; 5] = ["Config", "Entry", "Core", "Utilities", "Tests"];
+
+/// Files per layer. Tests and config go by name; a source file is an entry
+/// point by name or when nothing imports it, a utility when it sits at the
+/// bottom of the graph (importing nothing) with two or more importers, and
+/// core otherwise. Each layer is ordered by depth below the nearest entry
+/// point.
+pub fn assign(files: &[PathBuf], edges: &[DepEdge], config: &Config) -> [Vec<PathBuf>; 5] {
+    let importers: HashSet<&PathBuf> = edges.iter().map(|e| &e.from).collect();
+    let mut imported_by: HashMap<&PathBuf, usize> = HashMap::new();
+    for edge in edges {
+        *imported_by.entry(&edge.to).or_default() += 1;
+    }
+    let mut layers: [Vec<PathBuf>; 5] = Default::default();
+    for file in files {
+        let is_source = categories::in_bucket(file, Bucket::Source, config);
+        let uses = imported_by.get(file).copied().unwrap_or(0);
+        let index = if is_source && is_test(file) {
+            4
+        } else if categories::in_bucket(file, Bucket::Config, config) {
+            0
+        } else if !is_source {
+            continue;
+        } else if is_entry(file) || (uses == 0 && importers.contains(file)) {
+            1
+        } else if uses >= 2 && !importers.contains(file) {
+            3
+        } else {
+            2
+        };
+        if let Some(layer) = layers.get_mut(index) {
+            layer.push(file.clone());
+        }
+    }
+    let entries = layers.get(1).cloned().unwrap_or_default();
+    let depths = depths(&entries, edges);
+    for layer in &mut layers {
+        layer.sort_by_key(|f| (depths.get(f).copied().unwrap_or(usize::MAX), f.clone()));
+    }
+    layers
+}
+
+/// Import hops from the nearest of `entries` to each file it reaches.
+fn depths<'a>(entries: &'a [PathBuf], edges: &'a [DepEdge]) -> HashMap<&'a PathBuf, usize> {
+    let mut imports: HashMap<&PathBuf, Vec<&PathBuf>> = HashMap::new();
+    for edge in edges {
+        imports.entry(&edge.from).or_default().push(&edge.to);
+    }
+    let mut depth: HashMap<&PathBuf, usize> = entries.iter().map(|e| (e, 0)).collect();
+    let mut queue: VecDeque<&PathBuf> = entries.iter().collect();
+    while let Some(file) = queue.pop_front() {
+        let next = depth.get(file).map_or(0, |d| d + 1);
+        for dep in imports.get(file).into_iter().flatten() {
+            if !depth.contains_key(dep) {
+                depth.insert(dep, next);
+                queue.push_back(dep);
+            }
+        }
+    }
+    depth
+}
+
+fn is_test(p: &Path) -> bool {
+    let in_test_dir = p.components().any(|c| {
+        matches!(
+            c.as_os_str().to_str(),
+            Some("tests" | "test" | "__tests__" | "spec" | "benches")
+        )
+    });
+    let name = p.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    in_test_dir
+        || name.starts_with("test_")
+        || name == "tests.rs"
+        || [
+            "_test.go",
+            "_test.py",
+            "_test.rs",
+            ".test.ts",
+            ".test.js",
+            ".spec.ts",
+            ".spec.js",
+            ".test.tsx",
+            ".spec.tsx",
+        ]
+        .iter()
+        .any(|suffix| name.ends_with(suffix))
+}
+
+/// Conventional entry points, even when something imports them (a binary
+/// that also uses its own library, a package `__init__`).
+fn is_entry(p: &Path) -> bool {
+    let stem = p.file_stem().and_then(|n| n.to_str()).unwrap_or("");
+    matches!(
+        stem,
+        "main" | "lib" | "__main__" | "index" | "app" | "server" | "cli"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edge(from: &str, to: &str) -> DepEdge {
+        DepEdge {
+            from: PathBuf::from(from),
+            to: PathBuf::from(to),
+        }
+    }
+
+    #[test]
+    fn shared_leaves_are_utilities() {
+        let files: Vec<PathBuf> = [
+            "Cargo.toml",
+            "src/main.rs",
+            "src/state.rs",
+            "src/ui.rs",
+            "src/util.rs",
+            "src/once.rs",
+            "tests/cli.rs",
+        ]
+        .into_iter()
+        .map(PathBuf::from)
+        .collect();
+        let edges = [
+            edge("src/main.rs", "src/state.rs"),
+            edge("src/state.rs", "src/ui.rs"),
+            edge("src/state.rs", "src/util.rs"),
+            edge("src/ui.rs", "src/util.rs"),
+            edge("src/ui.rs", "src/once.rs"),
+        ];
+        let layers = assign(&files, &edges, &Config::default());
+        let names = |i: usize| -> Vec<&str> {
+            layers
+                .get(i)
+                .into_iter()
+                .flatten()
+                .filter_map(|p| p.to_str())
+                .collect()
+        };
+        assert_eq!(names(0), ["Cargo.toml"]);
+        assert_eq!(names(1), ["src/main.rs"]);
+        assert_eq!(names(2), ["src/state.rs", "src/ui.rs", "src/once.rs"]);
+        assert_eq!(names(3), ["src/util.rs"]);
+        assert_eq!(names(4), ["tests/cli.rs"]);
+    }
+}
@@ -0,0 +1,211 @@
+use std::collections::HashMap;
+
+/// Statements that style or group nodes without adding edges.
+const SKIPPED: &[&str] = &[
+    "subgraph",
+    "end",
+    "direction",
+    "classDef",
+    "class",
+    "style",
+    "linkStyle",
+    "click",
+];
+
+/// Closing arrows searched for after `-- text`, `== text` and `-. text`.
+const TEXT_ARROW_ENDS: &[&str] = &["-->", "---", "==>", "===", ".->", "-.-", "--x", "--o"];
+
+/// The parts of a Mermaid flowchart a SEMMAP needs: node labels by id and
+/// every link, read left to right.
+#[derive(Debug, Clone, Default)]
+pub struct Flowchart {
+    /// Label a node was first declared with, e.g. `a["src/a.rs"]`.
+    pub labels: HashMap<String, String>,
+    pub edges: Vec<Edge>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Edge {
+    pub from: String,
+    pub to: String,
+    /// 1-based line of the statement.
+    pub line: usize,
+}
+
+/// Every `graph` / `flowchart` block in `text`, up to its closing fence.
+/// Handles `A --> B & C`, `A --> B --> C`, `A -->|label| B`,
+/// `A -- label --> B`, dotted and thick links, node shapes with labels,
+/// `:::class` suffixes, `;` separators and `%%` comments.
+pub fn parse(text: &str) -> Flowchart {
+    let mut chart = Flowchart::default();
+    let mut in_graph = false;
+    for (n, line) in text.lines().enumerate() {
+        let t = line.trim();
+        if !in_graph {
+            in_graph = is_header(t);
+        } else if t.starts_with("```") || t.starts_with('#') {
+            in_graph = false;
+        } else {
+            for stmt in t.split(';') {
+                statement(stmt.trim(), n + 1, &mut chart);
+            }
+        }
+    }
+    chart
+}
+
+/// `graph TD`, `flowchart LR;` or a bare `graph`.
+fn is_header(t: &str) -> bool {
+    let mut words = t.trim_end_matches(';').split_whitespace();
+    let kind = words.next();
+    let dir = words.next();
+    matches!(kind, Some("graph" | "flowchart"))
+        && matches!(dir, None | Some("TD" | "TB" | "BT" | "LR" | "RL"))
+        && words.next().is_none()
+}
+
+fn statement(s: &str, line: usize, chart: &mut Flowchart) {
+    let first = s.split_whitespace().next().unwrap_or("");
+    if s.is_empty() || s.starts_with("%%") || SKIPPED.contains(&first) {
+        return;
+    }
+    let Some((mut from, mut rest)) = group(s, chart) else {
+        return;
+    };
+    while let Some(after) = link(rest) {
+        let Some((to, after)) = group(after, chart) else {
+            return;
+        };
+        for a in &from {
+            for b in &to {
+                chart.edges.push(Edge {
+                    from: a.clone(),
+                    to: b.clone(),
+                    line,
+                });
+            }
+        }
+        (from, rest) = (to, after);
+    }
+}
+
+/// `A`, or `A & B["label"] & C`.
+fn group<'a>(s: &'a str, chart: &mut Flowchart) -> Option<(Vec<String>, &'a str)> {
+    let (id, mut rest) = node(s, chart)?;
+    let mut ids = vec![id];
+    while let Some(after) = rest.trim_start().strip_prefix('&') {
+        let (id, after) = node(after, chart)?;
+        ids.push(id);
+        rest = after;
+    }
+    Some((ids, rest))
+}
+
+/// A node id with an optional shape and label, and an optional `:::class`.
+fn node<'a>(s: &'a str, chart: &mut Flowchart) -> Option<(String, &'a str)> {
+    let s = s.trim_start();
+    let len = id_len(s);
+    let id = s.get(..len).filter(|id| !id.is_empty())?;
+    let mut rest = s.get(len..)?;
+    if rest.starts_with(['[', '(', '{', '>']) {
+        let (label, after) = shape(rest)?;
+        chart
+            .labels
+            .entry(id.to_string())
+            .or_insert_with(|| label.to_string());
+        rest = after;
+    }
+    if let Some(after) = rest.strip_prefix(":::") {
+        rest = after.get(id_len(after)..)?;
+    }
+    Some((id.to_string(), rest))
+}
+
+/// Length of the node id at the start of `s`. A `-` belongs to the id
+/// unless it starts a link.
+fn id_len(s: &str) -> usize {
+    let mut chars = s.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let next = chars.peek().map(|&(_, n)| n);
+        let part_of_id = c.is_alphanumeric()
+            || matches!(c, '_' | '.' | '/')
+            || (c == '-' && !matches!(next, Some('-' | '.' | '>' | '=')));
+        if !part_of_id {
+            return i;
+        }
+    }
+    s.len()
+}
+
+/// The bracketed shape after a node id, `["label"]`, `(label)`,
+/// `([label])`, `{{label}}`, `>label]` and so on: its label and the rest.
+fn shape(s: &str) -> Option<(&str, &str)> {
+    let mut depth = 0usize;
+    let mut quoted = false;
+    for (i, c) in s.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '[' | '(' | '{' | '>' if !quoted && (c != '>' || i == 0) => depth += 1,
+            ']' | ')' | '}' if !quoted => {
+                depth = depth.saturating_sub(1);
+                if depth == 0 {
+                    let inner = s.get(1..i)?;
+                    let label = inner.trim_matches(|c: char| "[](){}/\\\"`> ".contains(c));
+                    return Some((label, s.get(i + 1..)?));
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Skip one link, with its `|text|` or `-- text -->` label, returning what
+/// follows it; `None` when `s` does not start with a link.
+fn link(s: &str) -> Option<&str> {
+    let s = s.trim_start();
+    let len = arrow_len(s)?;
+    let token = s.get(..len)?;
+    let mut rest = s.get(len..)?;
+    if matches!(token, "--" | "==" | "-.") && rest.starts_with(char::is_whitespace) {
+        let end = TEXT_ARROW_ENDS.iter().filter_map(|a| rest.find(a)).min()?;
+        let tail = rest.get(end..)?;
+        rest = tail.get(arrow_len(tail)?..)?;
+    }
+    let rest = rest.trim_start();
+    match rest.strip_prefix('|') {
+        Some(text) => text.find('|').and_then(|i| text.get(i + 1..)),
+        None => Some(rest),
+    }
+}
+
+/// Length of a link token such as `-->`, `---`, `-.->`, `==>`, `<-->`,
+/// `--x`, `o--o` or `~~~`.
+fn arrow_len(s: &str) -> Option<usize> {
+    let body_char = |c: char| matches!(c, '-' | '=' | '.' | '~');
+    let mut chars = s.chars().peekable();
+    let mut len = 0;
+    if matches!(chars.peek(), Some('<' | 'x' | 'o')) {
+        chars.next();
+        len += 1;
+    }
+    let mut body = 0;
+    while chars.next_if(|&c| body_char(c)).is_some() {
+        body += 1;
+    }
+    if body < 2 {
+        return None;
+    }
+    len += body;
+    match chars.next() {
+        Some('>') => len += 1,
+        Some('x' | 'o') if chars.peek().is_none_or(|c| c.is_whitespace() || *c == '|') => {
+            len += 1;
+        }
+        _ => {}
+    }
+    Some(len)
+}
+
+#[cfg(test)]
+mod tests;
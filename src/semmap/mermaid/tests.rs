@@ -0,0 +1,72 @@
+use super::*;
+
+fn edges(text: &str) -> Vec<(String, String)> {
+    parse(&format!("graph TD\n{text}\n"))
+        .edges
+        .into_iter()
+        .map(|e| (e.from, e.to))
+        .collect()
+}
+
+fn pairs(list: &[(&str, &str)]) -> Vec<(String, String)> {
+    list.iter()
+        .map(|(a, b)| ((*a).to_string(), (*b).to_string()))
+        .collect()
+}
+
+#[test]
+fn piped_label_fans_out_over_ampersands() {
+    assert_eq!(edges("A -->|x| B & C"), pairs(&[("A", "B"), ("A", "C")]));
+}
+
+#[test]
+fn text_label_then_chained_link() {
+    assert_eq!(
+        edges("A -- t --> B --> C"),
+        pairs(&[("A", "B"), ("B", "C")])
+    );
+}
+
+#[test]
+fn hyphen_inside_an_id_is_not_a_link() {
+    assert_eq!(id_len("a-b --> c"), 3);
+    assert_eq!(id_len("a-->b"), 1);
+    assert_eq!(edges("a-b --> c"), pairs(&[("a-b", "c")]));
+}
+
+#[test]
+fn class_suffix_and_labels() {
+    let chart = parse("flowchart LR;\n  a[\"src/a.rs\"]:::cls --> b:::cls; %% note\n");
+    assert_eq!(chart.labels.get("a").map(String::as_str), Some("src/a.rs"));
+    let found: Vec<_> = chart.edges.iter().map(|e| (&*e.from, &*e.to)).collect();
+    assert_eq!(found, [("a", "b")]);
+}
+
+#[test]
+fn arrow_tokens() {
+    assert_eq!(arrow_len("--> B"), Some(3));
+    assert_eq!(arrow_len("-.-> B"), Some(4));
+    assert_eq!(arrow_len("<--> B"), Some(4));
+    assert_eq!(arrow_len("--x B"), Some(3));
+    assert_eq!(arrow_len("o--o B"), Some(4));
+    assert_eq!(arrow_len("==> B"), Some(3));
+    assert_eq!(arrow_len("- B"), None);
+}
+
+#[test]
+fn link_skips_its_text() {
+    assert_eq!(link(" -->|uses| B").map(str::trim), Some("B"));
+    assert_eq!(link("-- uses --> B").map(str::trim), Some("B"));
+    assert_eq!(link("B"), None);
+}
+
+#[test]
+fn only_graph_blocks_are_read() {
+    let text = "A --> B\n```mermaid\ngraph TD\nC --> D\n```\nE --> F\n";
+    let found: Vec<_> = parse(text)
+        .edges
+        .into_iter()
+        .map(|e| (e.from, e.to, e.line))
+        .collect();
+    assert_eq!(found, [("C".to_string(), "D".to_string(), 4)]);
+}
@@ -0,0 +1,208 @@
+pub mod check;
+pub mod generate;
+mod layers;
+pub mod mermaid;
+mod nodes;
+mod notes;
+pub mod sources;
+
+use nodes::node_id;
+pub use nodes::{Node, NodeMap};
+pub use notes::FileNote;
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+pub const SEMMAP_FILE: &str = "SEMMAP.md";
+
+#[derive(Debug, Clone)]
+pub struct Layer {
+    pub index: u8,
+    pub label: String,
+    pub files: Vec<PathBuf>,
+    /// What the lines under each file entry say about it.
+    pub notes: HashMap<PathBuf, FileNote>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DepEdge {
+    pub from: PathBuf,
+    pub to: PathBuf,
+}
+
+#[derive(Debug, Clone)]
+pub struct SemmapData {
+    pub project_name: String,
+    pub layers: Vec<Layer>,
+    pub edges: Vec<DepEdge>,
+    /// Tags from the `## Legend` section with their meaning, in order.
+    pub legend: Vec<(String, String)>,
+}
+
+impl SemmapData {
+    pub fn all_files(&self) -> Vec<PathBuf> {
+        self.layers
+            .iter()
+            .flat_map(|l| l.files.iter().cloned())
+            .collect()
+    }
+
+    pub fn adjacency(&self) -> HashMap<PathBuf, Vec<PathBuf>> {
+        let mut map: HashMap<PathBuf, Vec<PathBuf>> = HashMap::new();
+        for edge in &self.edges {
+            map.entry(edge.from.clone())
+                .or_default()
+                .push(edge.to.clone());
+        }
+        map
+    }
+
+    pub fn note(&self, path: &Path) -> Option<&FileNote> {
+        self.layers.iter().find_map(|l| l.notes.get(path))
+    }
+
+    /// Tags some file carries: legend order first, then any the legend
+    /// leaves out, alphabetically.
+    pub fn tags(&self) -> Vec<String> {
+        let mut used: Vec<&String> = self
+            .layers
+            .iter()
+            .flat_map(|l| l.notes.values())
+            .flat_map(|n| &n.tags)
+            .collect();
+        used.sort();
+        used.dedup();
+        let mut tags: Vec<String> = self
+            .legend
+            .iter()
+            .map(|(tag, _)| tag)
+            .filter(|tag| used.contains(tag))
+            .cloned()
+            .collect();
+        tags.extend(
+            used.into_iter()
+                .filter(|tag| !self.legend.iter().any(|(t, _)| t == *tag))
+                .cloned(),
+        );
+        tags
+    }
+
+    /// Join `other`, read from another map file, into this one.
+    fn absorb(&mut self, other: Self) {
+        if self.project_name.is_empty() {
+            self.project_name = other.project_name;
+        }
+        for layer in other.layers {
+            match self.layers.iter_mut().find(|l| l.index == layer.index) {
+                Some(same) => {
+                    same.files.extend(layer.files);
+                    same.notes.extend(layer.notes);
+                }
+                None => self.layers.push(layer),
+            }
+        }
+        for (tag, meaning) in other.legend {
+            if !self.legend.iter().any(|(t, _)| *t == tag) {
+                self.legend.push((tag, meaning));
+            }
+        }
+    }
+
+    /// Files tagged `tag`.
+    pub fn tagged(&self, tag: &str) -> HashSet<&PathBuf> {
+        self.layers
+            .iter()
+            .flat_map(|l| &l.notes)
+            .filter(|(_, note)| note.tags.iter().any(|t| t == tag))
+            .map(|(path, _)| path)
+            .collect()
+    }
+}
+
+/// One map from every source: layers with the same index are joined,
+/// and each file's Mermaid graph may name files listed in another.
+/// `None` when there are no sources.
+pub fn parse_all(sources: &[(PathBuf, String)]) -> Option<SemmapData> {
+    let mut parts = sources.iter().map(|(_, text)| parse_text(text));
+    let mut data = parts.next()?;
+    for part in parts {
+        data.absorb(part);
+    }
+    data.layers.sort_by_key(|l| l.index);
+    let files = data.all_files();
+    let nodes = NodeMap::new(&files);
+    let edges: Vec<DepEdge> = sources
+        .iter()
+        .flat_map(|(_, text)| nodes.edges(&mermaid::parse(text)))
+        .collect();
+    data.edges = edges;
+    Some(data)
+}
+
+/// Layers, title and legend of one map file; edges need every file first.
+fn parse_text(text: &str) -> SemmapData {
+    let (project_name, layers) = parse_layers(text);
+    SemmapData {
+        project_name,
+        layers,
+        edges: Vec::new(),
+        legend: notes::legend(text),
+    }
+}
+
+fn parse_layers(text: &str) -> (String, Vec<Layer>) {
+    let mut project_name = String::new();
+    let mut layers: Vec<Layer> = Vec::new();
+    let mut current: Option<Layer> = None;
+    // The file entry whose note lines are being read.
+    let mut entry: Option<PathBuf> = None;
+
+    for line in text.lines() {
+        let t = line.trim();
+        if let Some(rest) = t.strip_prefix("# project --") {
+            project_name = rest.trim().to_string();
+        } else if let Some(rest) = t.strip_prefix("## Layer ") {
+            if let Some(prev) = current.take() {
+                layers.push(prev);
+            }
+            current = parse_layer_header(rest);
+            entry = None;
+        } else if let Some(layer) = current.as_mut() {
+            if let Some(path) = listed_path(t) {
+                let path = PathBuf::from(path);
+                layer.files.push(path.clone());
+                entry = Some(path);
+            } else if t.is_empty() || t.starts_with('#') {
+                entry = None;
+            } else if let Some(path) = &entry {
+                notes::add_line(layer.notes.entry(path.clone()).or_default(), t);
+            }
+        }
+    }
+    if let Some(prev) = current.take() {
+        layers.push(prev);
+    }
+    (project_name, layers)
+}
+
+fn parse_layer_header(rest: &str) -> Option<Layer> {
+    let (idx_str, label) = rest.split_once(" -- ")?;
+    let index: u8 = idx_str.trim().parse().ok()?;
+    Some(Layer {
+        index,
+        label: label.trim().to_string(),
+        files: Vec::new(),
+        notes: HashMap::new(),
+    })
+}
+
+/// The path of a file entry line such as `` `src/main.rs` ``.
+fn listed_path(t: &str) -> Option<&str> {
+    let inner = t.strip_prefix('`')?.strip_suffix('`')?;
+    looks_like_path(inner).then_some(inner)
+}
+
+fn looks_like_path(s: &str) -> bool {
+    s.contains('.') || s.contains('/') || s.contains('\\')
+}
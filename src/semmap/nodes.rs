@@ -0,0 +1,105 @@
+use super::{mermaid::Flowchart, DepEdge};
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+/// What a Mermaid node id stands for.
+pub enum Node<'a> {
+    File(&'a Path),
+    Unknown,
+    /// The id is the flattened form of more than one listed path.
+    Ambiguous(Vec<&'a Path>),
+}
+
+/// Resolves node ids to listed files: by the label the node was declared
+/// with, then by the id itself as a path, then by the flattened
+/// `src_foo_rs` form of a path when only one file flattens to it.
+pub struct NodeMap<'a> {
+    listed: HashSet<&'a Path>,
+    flat: HashMap<String, Vec<&'a Path>>,
+}
+
+impl<'a> NodeMap<'a> {
+    pub fn new(files: &'a [PathBuf]) -> Self {
+        let mut flat: HashMap<String, Vec<&Path>> = HashMap::new();
+        for file in files {
+            let paths = flat.entry(node_id(file)).or_default();
+            if !paths.contains(&file.as_path()) {
+                paths.push(file);
+            }
+        }
+        Self {
+            listed: files.iter().map(PathBuf::as_path).collect(),
+            flat,
+        }
+    }
+
+    pub fn resolve(&self, id: &str, chart: &Flowchart) -> Node<'a> {
+        let named = chart
+            .labels
+            .get(id)
+            .map(String::as_str)
+            .into_iter()
+            .chain([id]);
+        for name in named {
+            if let Some(path) = self.listed.get(Path::new(name)) {
+                return Node::File(path);
+            }
+        }
+        match self.flat.get(id).map(Vec::as_slice) {
+            Some([path]) => Node::File(path),
+            Some(paths) if !paths.is_empty() => Node::Ambiguous(paths.to_vec()),
+            _ => Node::Unknown,
+        }
+    }
+
+    fn file(&self, id: &str, chart: &Flowchart) -> Option<PathBuf> {
+        match self.resolve(id, chart) {
+            Node::File(path) => Some(path.to_path_buf()),
+            Node::Unknown | Node::Ambiguous(_) => None,
+        }
+    }
+
+    /// The edges of `chart` whose ends both resolve.
+    pub fn edges(&self, chart: &Flowchart) -> Vec<DepEdge> {
+        chart
+            .edges
+            .iter()
+            .filter_map(|e| {
+                Some(DepEdge {
+                    from: self.file(&e.from, chart)?,
+                    to: self.file(&e.to, chart)?,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Mermaid-safe id for a path, as written by `pakr semmap init`.
+pub fn node_id(path: &Path) -> String {
+    path.to_string_lossy().replace(['/', '\\', '.', '-'], "_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn node_ids_resolve_by_label_path_then_flattened_id() {
+        let files: Vec<PathBuf> = ["src/a-b.rs", "src/a_b.rs", "src/c.rs"]
+            .iter()
+            .map(PathBuf::from)
+            .collect();
+        let nodes = NodeMap::new(&files);
+        let chart = crate::semmap::mermaid::parse("graph TD\n  x[\"src/a_b.rs\"] --> src/c.rs\n");
+        let c = Path::new("src/c.rs");
+        assert!(
+            matches!(nodes.resolve("x", &chart), Node::File(p) if p == Path::new("src/a_b.rs"))
+        );
+        assert!(matches!(nodes.resolve("src/c.rs", &chart), Node::File(p) if p == c));
+        assert!(matches!(nodes.resolve("src_c_rs", &chart), Node::File(p) if p == c));
+        assert!(matches!(nodes.resolve("src_a_b_rs", &chart), Node::Ambiguous(p) if p.len() == 2));
+        assert!(matches!(nodes.resolve("nope", &chart), Node::Unknown));
+    }
+}
@@ -0,0 +1,125 @@
+use super::listed_path;
+use std::{collections::HashMap, path::PathBuf};
+
+/// The lines under a file entry: free text, `[TAG]` markers pulled out of
+/// it, and the names on a `→ Exports:` line.
+#[derive(Debug, Clone, Default)]
+pub struct FileNote {
+    pub description: String,
+    pub tags: Vec<String>,
+    pub exports: Vec<String>,
+}
+
+impl FileNote {
+    /// `[CORE] Orchestrates the scan` on one line, for headers and titles.
+    pub fn summary(&self) -> String {
+        let mut parts: Vec<String> = self.tags.iter().map(|t| format!("[{t}]")).collect();
+        if !self.description.is_empty() {
+            parts.push(self.description.clone());
+        }
+        parts.join(" ")
+    }
+}
+
+/// Fold one line under a file entry into its note.
+pub fn add_line(note: &mut FileNote, t: &str) {
+    let exports = t
+        .trim_start_matches(['→', '-', '>', ' '])
+        .strip_prefix("Exports:");
+    if let Some(names) = exports {
+        note.exports.extend(
+            names
+                .split(',')
+                .map(str::trim)
+                .filter(|n| !n.is_empty())
+                .map(String::from),
+        );
+        return;
+    }
+    let mut words = Vec::new();
+    for word in t.split_whitespace() {
+        match tag_of(word) {
+            Some(tag) if !note.tags.iter().any(|t| t == tag) => note.tags.push(tag.to_string()),
+            Some(_) => {}
+            None => words.push(word),
+        }
+    }
+    if !words.is_empty() {
+        if !note.description.is_empty() {
+            note.description.push(' ');
+        }
+        note.description.push_str(&words.join(" "));
+    }
+}
+
+/// `CORE` for a `[CORE]` marker: upper case letters, digits, `_` and `-`.
+fn tag_of(word: &str) -> Option<&str> {
+    let tag = word.strip_prefix('[')?.strip_suffix(']')?;
+    let valid = tag.starts_with(|c: char| c.is_ascii_uppercase())
+        && tag
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_' || c == '-');
+    valid.then_some(tag)
+}
+
+/// `` `[ENTRY]` Application entry point `` lines under `## Legend`.
+pub fn legend(text: &str) -> Vec<(String, String)> {
+    let mut legend = Vec::new();
+    let mut in_legend = false;
+    for line in text.lines() {
+        let t = line.trim();
+        if t.starts_with('#') {
+            in_legend = t == "## Legend";
+            continue;
+        }
+        let Some((marker, meaning)) = t
+            .strip_prefix('`')
+            .and_then(|rest| rest.split_once('`'))
+            .filter(|_| in_legend)
+        else {
+            continue;
+        };
+        if let Some(tag) = tag_of(marker) {
+            legend.push((tag.to_string(), meaning.trim().to_string()));
+        }
+    }
+    legend
+}
+
+/// Description lines under each file entry of a SEMMAP.md: everything after
+/// the backtick path up to the next blank line.
+pub fn descriptions(text: &str) -> HashMap<PathBuf, Vec<String>> {
+    let mut out: HashMap<PathBuf, Vec<String>> = HashMap::new();
+    let mut current: Option<PathBuf> = None;
+    let mut in_layer = false;
+    for line in text.lines() {
+        let t = line.trim();
+        if t.starts_with('#') || t.starts_with("```") {
+            in_layer = t.starts_with("## Layer ");
+            current = None;
+        } else if t.is_empty() {
+            current = None;
+        } else if let Some(path) = listed_path(t).filter(|_| in_layer) {
+            current = Some(PathBuf::from(path));
+        } else if let Some(path) = &current {
+            out.entry(path.clone()).or_default().push(line.to_string());
+        }
+    }
+    out
+}
+
+/// Raw lines of the `## Legend` section, without surrounding blank lines.
+pub fn legend_lines(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = text
+        .lines()
+        .skip_while(|l| l.trim() != "## Legend")
+        .skip(1)
+        .take_while(|l| !l.trim_start().starts_with('#'))
+        .map(str::to_string)
+        .collect();
+    while lines.last().is_some_and(|l| l.trim().is_empty()) {
+        lines.pop();
+    }
+    let blank = lines.iter().take_while(|l| l.trim().is_empty()).count();
+    lines.split_off(blank)
+}
@@ -0,0 +1,61 @@
+use super::SEMMAP_FILE;
+use crate::config::Config;
+use anyhow::{Context, Result};
+use globset::GlobBuilder;
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Where the map is looked for when `.pakr.toml` does not say: the first
+/// of these that matches anything.
+const DEFAULT_SOURCES: &[&str] = &[SEMMAP_FILE, "docs/semmap/*.md"];
+
+/// The map files of `root` with their text, in reading order. `files` are
+/// the scanned paths globs are matched against.
+pub fn read(root: &Path, config: &Config, files: &[PathBuf]) -> Result<Vec<(PathBuf, String)>> {
+    let mut paths = Vec::new();
+    match &config.semmap {
+        Some(patterns) => {
+            for pattern in patterns {
+                paths.extend(matching(root, pattern, files)?);
+            }
+        }
+        None => {
+            for pattern in DEFAULT_SOURCES {
+                paths = matching(root, pattern, files)?;
+                if !paths.is_empty() {
+                    break;
+                }
+            }
+        }
+    }
+    let mut seen = HashSet::new();
+    paths.retain(|p| seen.insert(p.clone()));
+    Ok(paths
+        .into_iter()
+        .filter_map(|p| Some((p.clone(), fs::read_to_string(root.join(&p)).ok()?)))
+        .collect())
+}
+
+/// A plain root-relative path when it is a file; otherwise a glob over
+/// `files`, where `*` stays within one folder.
+fn matching(root: &Path, pattern: &str, files: &[PathBuf]) -> Result<Vec<PathBuf>> {
+    if !pattern.contains(['*', '?', '[', '{']) {
+        let path = PathBuf::from(pattern);
+        return Ok(if root.join(&path).is_file() {
+            vec![path]
+        } else {
+            Vec::new()
+        });
+    }
+    let glob = GlobBuilder::new(pattern)
+        .literal_separator(true)
+        .build()
+        .with_context(|| format!("invalid semmap glob `{pattern}`"))?
+        .compile_matcher();
+    let mut out: Vec<PathBuf> = files.iter().filter(|f| glob.is_match(f)).cloned().collect();
+    out.sort();
+    Ok(out)
+}
@@ -0,0 +1,121 @@
+use crate::{
+    snapshot::Snapshot,
+    types::{AppState, FileNode, Focus},
+};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashSet,
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+/// What the TUI looked like on quit, restored on the next launch in the
+/// same root.
+#[derive(Debug, Serialize, Deserialize)]
+struct Session {
+    root: PathBuf,
+    collapsed: Vec<PathBuf>,
+    all_collapsed: bool,
+    focus: Focus,
+    cat_cursor: usize,
+    sub_cursor: usize,
+    tree_cursor: usize,
+    selection: Snapshot,
+}
+
+pub fn save(state: &AppState) -> Result<()> {
+    let path = file_for(&state.root).context("no cache directory")?;
+    let session = Session {
+        root: state.root.clone(),
+        collapsed: collapsed_dirs(&state.tree),
+        all_collapsed: state.all_collapsed,
+        focus: state.focus,
+        cat_cursor: state.cat_cursor,
+        sub_cursor: state.sub_cursor,
+        tree_cursor: state.tree_cursor(),
+        selection: Snapshot::capture(state),
+    };
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&path, toml::to_string(&session)?)
+        .with_context(|| format!("writing {}", path.display()))
+}
+
+/// Restore the last session for this root; `false` when there is none
+/// (or it cannot be read, which is treated the same).
+pub fn restore(state: &mut AppState) -> bool {
+    let Some(session) = file_for(&state.root)
+        .and_then(|p| fs::read_to_string(p).ok())
+        .and_then(|text| toml::from_str::<Session>(&text).ok())
+        .filter(|s| s.root == state.root)
+    else {
+        return false;
+    };
+    let missing = session.selection.apply(state);
+    let collapsed: HashSet<PathBuf> = session.collapsed.into_iter().collect();
+    apply_collapsed(&mut state.tree, &collapsed);
+    state.all_collapsed = session.all_collapsed;
+    state.focus = session.focus;
+    state.cat_cursor = session.cat_cursor;
+    state.sub_cursor = session.sub_cursor;
+    let rows = state.visible_items().len();
+    state.set_tree_cursor(session.tree_cursor.min(rows.saturating_sub(1)));
+    state.clamp_cursors();
+    state.status = if missing.is_empty() {
+        "restored last session  [R] resets the selection".to_string()
+    } else {
+        format!(
+            "restored last session; dropped {} missing path(s)",
+            missing.len()
+        )
+    };
+    true
+}
+
+/// `$XDG_CACHE_HOME/pakr/sessions/<hash of root>.toml`.
+fn file_for(root: &Path) -> Option<PathBuf> {
+    let base = env::var_os("XDG_CACHE_HOME")
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|h| PathBuf::from(h).join(".cache")))?;
+    let name = format!("{:016x}.toml", fnv1a(root.as_os_str().as_encoded_bytes()));
+    Some(base.join("pakr").join("sessions").join(name))
+}
+
+/// Stable across builds, unlike `DefaultHasher`.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Folders currently collapsed, for restoring the tree's shape later.
+fn collapsed_dirs(node: &FileNode) -> Vec<PathBuf> {
+    let mut out = Vec::new();
+    push_collapsed(node, &mut out);
+    out
+}
+
+fn push_collapsed(node: &FileNode, out: &mut Vec<PathBuf>) {
+    if !node.is_dir {
+        return;
+    }
+    if !node.expanded {
+        out.push(node.path.clone());
+    }
+    for child in &node.children {
+        push_collapsed(child, out);
+    }
+}
+
+/// Collapse exactly the folders in `collapsed`, expanding every other one.
+fn apply_collapsed(node: &mut FileNode, collapsed: &HashSet<PathBuf>) {
+    if node.is_dir {
+        node.expanded = !collapsed.contains(&node.path);
+        for child in &mut node.children {
+            apply_collapsed(child, collapsed);
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use crate::{
+    path_list::PathList,
+    semmap::SemmapData,
+    types::{AppState, ExtMode},
+};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Everything that decides which files get packed, keyed by name and path
+/// so it survives rescans.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Snapshot {
+    /// Names of enabled categories.
+    pub categories: Vec<String>,
+    /// Names of enabled subsystems; `None` when captured before the import
+    /// graph was read, so applying it leaves subsystems alone.
+    pub subsystems: Option<Vec<String>>,
+    /// Extensions forced into / out of the selection.
+    pub include_types: Vec<String>,
+    pub exclude_types: Vec<String>,
+    pub exclude: Vec<PathBuf>,
+    /// Files and folders checked in the tree.
+    pub include: Vec<PathBuf>,
+    pub manual_includes: Vec<PathBuf>,
+    pub respect_gitignore: bool,
+    pub format: String,
+    pub outline: bool,
+    pub full_files: Vec<PathBuf>,
+    /// SEMMAP tag the selection is narrowed to.
+    pub tag: Option<String>,
+    pub describe: bool,
+}
+
+impl Snapshot {
+    pub fn capture(state: &AppState) -> Self {
+        let types = |mode: ExtMode| -> Vec<String> {
+            state
+                .extensions
+                .iter()
+                .filter(|g| g.mode == mode)
+                .map(|g| g.ext.clone())
+                .collect()
+        };
+        Self {
+            categories: state
+                .categories
+                .iter()
+                .filter(|c| c.enabled)
+                .map(|c| c.name().to_string())
+                .collect(),
+            subsystems: match &state.analysis {
+                Some(pending) => pending.wanted.clone(),
+                None => Some(
+                    state
+                        .subsystems
+                        .iter()
+                        .filter(|s| s.enabled)
+                        .map(|s| s.name.clone())
+                        .collect(),
+                ),
+            },
+            include_types: types(ExtMode::Include),
+            exclude_types: types(ExtMode::Exclude),
+            exclude: state.exclude.items().to_vec(),
+            include: state.include.items().to_vec(),
+            manual_includes: state.manual_includes.clone(),
+            respect_gitignore: state.respect_gitignore,
+            format: state.format.label().to_string(),
+            outline: state.outline,
+            full_files: state.full_files.clone(),
+            tag: state.tag_filter.clone(),
+            describe: state.describe,
+        }
+    }
+
+    /// Restore this selection onto `state`. Paths that no longer exist under
+    /// the root are dropped and returned so the caller can report them.
+    pub fn apply(&self, state: &mut AppState) -> Vec<PathBuf> {
+        for cat in &mut state.categories {
+            cat.enabled = self.categories.iter().any(|n| n == cat.name());
+        }
+        if let Some(pending) = &mut state.analysis {
+            if self.subsystems.is_some() {
+                pending.wanted.clone_from(&self.subsystems);
+            }
+        } else if let Some(names) = &self.subsystems {
+            for sub in &mut state.subsystems {
+                sub.enabled = names.contains(&sub.name);
+            }
+        }
+        for group in &mut state.extensions {
+            group.mode = if self.include_types.contains(&group.ext) {
+                ExtMode::Include
+            } else if self.exclude_types.contains(&group.ext) {
+                ExtMode::Exclude
+            } else {
+                ExtMode::Inherit
+            };
+        }
+        state.respect_gitignore = self.respect_gitignore && state.has_gitignore;
+        if let Ok(format) = self.format.parse() {
+            state.format = format;
+        }
+        state.outline = self.outline;
+        state.describe = self.describe;
+        let tags = state.graph.as_ref().map_or_else(Vec::new, SemmapData::tags);
+        state.tag_filter = self.tag.clone().filter(|t| tags.contains(t));
+
+        let mut missing = Vec::new();
+        let mut existing = |paths: &[PathBuf]| -> Vec<PathBuf> {
+            let (keep, gone): (Vec<PathBuf>, Vec<PathBuf>) = paths
+                .iter()
+                .cloned()
+                .partition(|p| state.root.join(p).exists());
+            missing.extend(gone);
+            keep
+        };
+        let exclude = existing(&self.exclude);
+        let include = existing(&self.include);
+        let manual = existing(&self.manual_includes);
+        let full = existing(&self.full_files);
+        state.exclude = list(exclude);
+        state.include = list(include);
+        state.manual_includes = manual;
+        state.full_files = full;
+        state.clamp_cursors();
+        missing.sort();
+        missing.dedup();
+        missing
+    }
+}
+
+fn list(paths: Vec<PathBuf>) -> PathList {
+    let mut list = PathList::default();
+    for path in paths {
+        list.insert(path);
+    }
+    list
+}
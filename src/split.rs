@@ -0,0 +1,171 @@
+use crate::format::Document;
+
+/// Group documents into parts of at most `limit` tokens. Files stay whole
+/// whenever they fit in a part on their own; larger ones are cut at line
+/// boundaries and carry a `k/m, lines a-b` continuation note.
+///
+/// `measure` counts the tokens of a rendered part, so the part header and
+/// each document's delimiters, path, description and escaping count toward
+/// the limit along with the file text.
+pub fn into_parts(
+    docs: Vec<Document>,
+    limit: usize,
+    count: &dyn Fn(&str) -> usize,
+    measure: &dyn Fn(&[Document]) -> usize,
+) -> Vec<Vec<Document>> {
+    let header = measure(&[]);
+    let room = limit.saturating_sub(header).max(1);
+    let cost = |doc: &Document| measure(std::slice::from_ref(doc)).saturating_sub(header);
+    let mut parts: Vec<Vec<Document>> = Vec::new();
+    let mut current: Vec<Document> = Vec::new();
+    let mut used = 0usize;
+
+    for doc in docs {
+        let whole = cost(&doc);
+        let pieces = if whole > room {
+            fit_doc(&doc, room, count, &cost)
+        } else {
+            vec![(doc, whole)]
+        };
+        for (piece, piece_cost) in pieces {
+            if used + piece_cost > room && !current.is_empty() {
+                parts.push(std::mem::take(&mut current));
+                used = 0;
+            }
+            used += piece_cost;
+            current.push(piece);
+        }
+    }
+    if !current.is_empty() || parts.is_empty() {
+        parts.push(current);
+    }
+    parts
+}
+
+/// Cut `doc` into pieces whose rendered cost fits in `room`, with their
+/// costs. The text budget starts at `room` and shrinks while delimiters,
+/// escaping or rounding still push a piece over.
+fn fit_doc(
+    doc: &Document,
+    room: usize,
+    count: &dyn Fn(&str) -> usize,
+    cost: &dyn Fn(&Document) -> usize,
+) -> Vec<(Document, usize)> {
+    let mut text_room = room;
+    loop {
+        let pieces: Vec<(Document, usize)> = split_doc(doc.clone(), text_room, count)
+            .into_iter()
+            .map(|piece| {
+                let c = cost(&piece);
+                (piece, c)
+            })
+            .collect();
+        let worst = pieces.iter().map(|(_, c)| *c).max().unwrap_or(0);
+        if worst <= room || text_room == 1 {
+            return pieces;
+        }
+        let scaled = text_room.saturating_mul(room) / worst.max(1);
+        text_room = scaled.min(text_room - 1).max(1);
+    }
+}
+
+fn split_doc(doc: Document, limit: usize, count: &dyn Fn(&str) -> usize) -> Vec<Document> {
+    let Ok(content) = doc.content else {
+        return vec![doc];
+    };
+
+    // (first line, last line, text, tokens) per chunk, 1-based line numbers.
+    let mut chunks: Vec<(usize, usize, String, usize)> = Vec::new();
+    let mut text = String::new();
+    let (mut first, mut last, mut tokens) = (1usize, 1usize, 0usize);
+    for (i, line) in content.split_inclusive('\n').enumerate() {
+        for piece in cut_line(line, limit, count) {
+            let piece_tokens = count(piece);
+            if tokens + piece_tokens > limit && !text.is_empty() {
+                chunks.push((first, last, std::mem::take(&mut text), tokens));
+                first = i + 1;
+                tokens = 0;
+            }
+            text.push_str(piece);
+            tokens += piece_tokens;
+            last = i + 1;
+        }
+    }
+    if !text.is_empty() {
+        chunks.push((first, last, text, tokens));
+    }
+
+    let total = chunks.len();
+    chunks
+        .into_iter()
+        .enumerate()
+        .map(|(k, (a, b, text, tokens))| Document {
+            path: doc.path.clone(),
+            note: Some(match &doc.note {
+                Some(prev) => format!("{prev}, {}/{total}, lines {a}-{b}", k + 1),
+                None => format!("{}/{total}, lines {a}-{b}", k + 1),
+            }),
+            content: Ok(text),
+            tokens,
+            lang: doc.lang,
+            description: doc.description.clone().filter(|_| k == 0),
+        })
+        .collect()
+}
+
+/// A line as pieces of at most `limit` tokens each; lines that fit stay
+/// whole, so only an oversized line is cut mid-line.
+fn cut_line<'a>(line: &'a str, limit: usize, count: &dyn Fn(&str) -> usize) -> Vec<&'a str> {
+    let mut pieces = Vec::new();
+    let mut rest = line;
+    while count(rest) > limit {
+        let ends: Vec<usize> = rest.char_indices().skip(1).map(|(i, _)| i).collect();
+        let fits = ends.partition_point(|&end| count(rest.get(..end).unwrap_or(rest)) <= limit);
+        let Some(&end) = ends.get(fits.saturating_sub(1)) else {
+            break;
+        };
+        let (head, tail) = rest.split_at(end);
+        pieces.push(head);
+        rest = tail;
+    }
+    pieces.push(rest);
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn doc(content: String) -> Document {
+        Document {
+            path: "a.txt".to_string(),
+            note: None,
+            content: Ok(content),
+            tokens: 0,
+            lang: None,
+            description: None,
+        }
+    }
+
+    #[test]
+    fn only_an_oversized_line_is_cut() {
+        let count = |text: &str| text.len();
+        let measure = |docs: &[Document]| {
+            10 + docs
+                .iter()
+                .map(|d| 5 + d.content.as_ref().map_or(0, String::len))
+                .sum::<usize>()
+        };
+        let short = "x".repeat(9) + "\n";
+        let text = short.repeat(40) + &"y".repeat(250) + "\n" + &short.repeat(40);
+        let parts = into_parts(vec![doc(text.clone())], 110, &count, &measure);
+        assert!(parts.iter().all(|p| measure(p) <= 110));
+        assert!(parts.len() <= 14, "{} parts", parts.len());
+        let joined: String = parts
+            .iter()
+            .flatten()
+            .filter_map(|d| d.content.as_deref().ok())
+            .collect();
+        assert_eq!(joined, text);
+    }
+}
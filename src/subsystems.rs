@@ -4,6 +4,9 @@ use std::{
     path::{Path, PathBuf},
 };
 
+/// Name of the subsystem for files outside every import cluster.
+const OTHER: &str = "Other";
+
 pub fn build(data: &SemmapData) -> Vec<Subsystem> {
     find_components(data)
         .into_iter()
@@ -17,6 +20,34 @@ pub fn build(data: &SemmapData) -> Vec<Subsystem> {
         .collect()
 }
 
+/// Subsystems of an import graph read from the sources, plus an "Other"
+/// subsystem holding every file of `all` outside the clusters, so a file
+/// with no imports stays selected.
+pub fn build_with_rest<'a>(
+    data: &SemmapData,
+    all: impl IntoIterator<Item = &'a PathBuf>,
+) -> Vec<Subsystem> {
+    let mut subs = build(data);
+    if subs.is_empty() {
+        return subs;
+    }
+    let clustered: HashSet<&PathBuf> = subs.iter().flat_map(|s| &s.files).collect();
+    let mut rest: Vec<PathBuf> = all
+        .into_iter()
+        .filter(|p| !clustered.contains(p))
+        .cloned()
+        .collect();
+    rest.sort();
+    if !rest.is_empty() {
+        subs.push(Subsystem {
+            name: OTHER.to_string(),
+            files: rest,
+            enabled: true,
+        });
+    }
+    subs
+}
+
 fn find_components(data: &SemmapData) -> Vec<Vec<PathBuf>> {
     let adj = build_adj(data);
     let mut visited: HashSet<PathBuf> = HashSet::new();
@@ -0,0 +1,185 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        mpsc::{self, Sender},
+        Arc, Mutex, MutexGuard, PoisonError,
+    },
+    thread,
+    time::{Duration, Instant, SystemTime},
+};
+
+/// Shared with the counting thread, hence `Send + Sync`.
+pub trait Tokenizer: Send + Sync {
+    fn name(&self) -> &str;
+    fn count(&self, text: &str) -> usize;
+    /// Whether counts depend on file content; the heuristic only needs the size.
+    fn needs_content(&self) -> bool {
+        true
+    }
+}
+
+/// `bytes / 3` — errs slightly high, which is what you want when planning context.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Heuristic;
+
+impl Heuristic {
+    pub fn from_bytes(bytes: u64) -> usize {
+        usize::try_from(bytes / 3).unwrap_or(usize::MAX / 3)
+    }
+}
+
+impl Tokenizer for Heuristic {
+    fn name(&self) -> &'static str {
+        "bytes/3"
+    }
+    fn count(&self, text: &str) -> usize {
+        text.len() / 3
+    }
+    fn needs_content(&self) -> bool {
+        false
+    }
+}
+
+/// How long a cached count is trusted before the file is stat'ed again.
+const RESTAT: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Copy)]
+struct Cached {
+    size: u64,
+    mtime: Option<SystemTime>,
+    /// When the file was last seen with this size and mtime.
+    checked: Instant,
+    tokens: usize,
+}
+
+type Cache = Arc<Mutex<HashMap<PathBuf, Cached>>>;
+
+/// Counts tokens per file, remembering results until the file's size or
+/// mtime changes so the tree and summary can be redrawn every frame.
+pub struct TokenCounter {
+    tokenizer: Arc<dyn Tokenizer>,
+    cache: Cache,
+    /// Set by [`Self::count_in_background`]: files are then counted on a
+    /// worker thread and show the estimate until their count arrives.
+    worker: Option<Sender<(PathBuf, PathBuf)>>,
+    /// Files handed to the worker and not counted yet.
+    queued: Arc<Mutex<HashSet<PathBuf>>>,
+}
+
+impl std::fmt::Debug for TokenCounter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "TokenCounter({})", self.tokenizer.name())
+    }
+}
+
+impl Default for TokenCounter {
+    fn default() -> Self {
+        Self::new(Box::new(Heuristic))
+    }
+}
+
+impl TokenCounter {
+    pub fn new(tokenizer: Box<dyn Tokenizer>) -> Self {
+        Self {
+            tokenizer: Arc::from(tokenizer),
+            cache: Arc::default(),
+            worker: None,
+            queued: Arc::default(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        self.tokenizer.name()
+    }
+
+    pub fn count_text(&self, text: &str) -> usize {
+        self.tokenizer.count(text)
+    }
+
+    /// Count files on a worker thread from now on, so reading and
+    /// tokenizing a large tree never blocks a frame.
+    pub fn count_in_background(&mut self) {
+        if !self.tokenizer.needs_content() || self.worker.is_some() {
+            return;
+        }
+        let (tx, rx) = mpsc::channel::<(PathBuf, PathBuf)>();
+        let tokenizer = Arc::clone(&self.tokenizer);
+        let cache = Arc::clone(&self.cache);
+        let queued = Arc::clone(&self.queued);
+        thread::spawn(move || {
+            for (root, rel) in rx {
+                if let Some(entry) = count(tokenizer.as_ref(), &root, &rel) {
+                    lock(&cache).insert(rel.clone(), entry);
+                }
+                lock(&queued).remove(&rel);
+            }
+        });
+        self.worker = Some(tx);
+    }
+
+    /// Token count of `root/rel`; unreadable or non-UTF-8 files fall back to
+    /// the heuristic, as do files still waiting for the worker thread.
+    pub fn count_file(&self, root: &Path, rel: &Path, size: u64) -> usize {
+        if !self.tokenizer.needs_content() {
+            return Heuristic::from_bytes(size);
+        }
+        if let Some(tokens) = self.cached(root, rel) {
+            return tokens;
+        }
+        if let Some(worker) = &self.worker {
+            if lock(&self.queued).insert(rel.to_path_buf()) {
+                let _ = worker.send((root.to_path_buf(), rel.to_path_buf()));
+            }
+            return Heuristic::from_bytes(size);
+        }
+        let Some(entry) = count(self.tokenizer.as_ref(), root, rel) else {
+            return Heuristic::from_bytes(size);
+        };
+        lock(&self.cache).insert(rel.to_path_buf(), entry);
+        entry.tokens
+    }
+
+    /// The cached count, if the file still has the size and mtime it was
+    /// counted at. The file is stat'ed again once [`RESTAT`] has passed.
+    fn cached(&self, root: &Path, rel: &Path) -> Option<usize> {
+        let mut cache = lock(&self.cache);
+        let hit = cache.get_mut(rel)?;
+        if hit.checked.elapsed() < RESTAT {
+            return Some(hit.tokens);
+        }
+        let (size, mtime) = stat(&root.join(rel))?;
+        if (size, mtime) != (hit.size, hit.mtime) {
+            return None;
+        }
+        hit.checked = Instant::now();
+        Some(hit.tokens)
+    }
+}
+
+/// Read and count one file, recording the size and mtime it had.
+fn count(tokenizer: &dyn Tokenizer, root: &Path, rel: &Path) -> Option<Cached> {
+    let path = root.join(rel);
+    let (size, mtime) = stat(&path)?;
+    let tokens = fs::read_to_string(&path).map_or_else(
+        |_| Heuristic::from_bytes(size),
+        |text| tokenizer.count(&text),
+    );
+    Some(Cached {
+        size,
+        mtime,
+        checked: Instant::now(),
+        tokens,
+    })
+}
+
+fn stat(path: &Path) -> Option<(u64, Option<SystemTime>)> {
+    let meta = fs::metadata(path).ok()?;
+    Some((meta.len(), meta.modified().ok()))
+}
+
+/// A poisoned lock only means a count panicked; the map itself is intact.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
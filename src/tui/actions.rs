@@ -0,0 +1,177 @@
+use crate::{
+    budget,
+    deps::{self, Direction},
+    diff::DiffMode,
+    scanner, selection,
+    semmap::SemmapData,
+    types::AppState,
+};
+use std::path::PathBuf;
+
+/// Dropped paths named in the auto-fit status; the rest are counted.
+const FIT_NAMES: usize = 3;
+
+/// Hop limits offered by the `L` key, cycled in order after "unlimited".
+const DEPTH_PRESETS: &[usize] = &[1, 2, 3];
+
+/// Lines moved per PgUp/PgDn in the preview.
+pub const PREVIEW_PAGE: isize = 20;
+
+pub fn cycle_format(state: &mut AppState) {
+    state.format = state.format.next();
+    state.status = format!("format: {}", state.format.label());
+}
+
+pub fn toggle_outline(state: &mut AppState) {
+    state.outline = !state.outline;
+    state.status = if state.outline {
+        "outline export: signatures only, [o] keeps a file in full".to_string()
+    } else {
+        "full export".to_string()
+    };
+}
+
+pub fn toggle_describe(state: &mut AppState) {
+    state.describe = !state.describe;
+    state.status = if state.describe {
+        "export: SEMMAP description above each file".to_string()
+    } else {
+        "export: no descriptions".to_string()
+    };
+}
+
+/// Narrow the selection to the next SEMMAP tag in use, then back to all.
+pub fn cycle_tag(state: &mut AppState) {
+    let tags = state.graph.as_ref().map_or_else(Vec::new, SemmapData::tags);
+    if tags.is_empty() {
+        state.status = "no tagged files in SEMMAP.md".to_string();
+        return;
+    }
+    let next = match &state.tag_filter {
+        None => 0,
+        Some(cur) => tags.iter().position(|t| t == cur).map_or(0, |i| i + 1),
+    };
+    state.tag_filter = tags.get(next).cloned();
+    state.status = match &state.tag_filter {
+        Some(tag) => format!(
+            "tag [{tag}]: {} file(s) selected  [T] next tag",
+            state.selected_paths().len()
+        ),
+        None => "tag filter off".to_string(),
+    };
+}
+
+/// Back to the selection pakr computed at startup, before any session,
+/// profile or edit.
+pub fn reset_selection(state: &mut AppState) {
+    let defaults = state.defaults.clone();
+    defaults.apply(state);
+    state.status = "selection reset to defaults".to_string();
+}
+
+pub fn toggle_preview(state: &mut AppState) {
+    state.preview.open = !state.preview.open;
+    state.status = if state.preview.open {
+        "preview: follows the tree cursor or mouse, [PgUp/PgDn] scroll".to_string()
+    } else {
+        String::new()
+    };
+}
+
+/// Scroll the preview by `lines`, up when negative; the panel clamps the far end.
+pub fn scroll_preview(state: &mut AppState, lines: isize) {
+    if state.preview.open {
+        state.preview.scroll = state.preview.scroll.saturating_add_signed(lines);
+    }
+}
+
+pub fn cycle_diff(state: &mut AppState) {
+    state.diff.mode = state.diff.mode.next();
+    state.pending_parts.clear();
+    state.status = match state.diff.mode {
+        DiffMode::Off => "diff off".to_string(),
+        DiffMode::Append => format!("export + git diff {}", state.diff.base),
+        DiffMode::Only => format!("export only git diff {}", state.diff.base),
+    };
+}
+
+pub fn cycle_budget(state: &mut AppState) {
+    state.budget = budget::next_preset(state.budget);
+    state.status = state.budget.map_or_else(
+        || "budget off".to_string(),
+        |b| format!("budget: {} tok", budget::fmt_tokens(b)),
+    );
+}
+
+pub fn fit_budget(state: &mut AppState) {
+    if state.budget.is_none() {
+        state.status = "set a budget with [b] first".to_string();
+        return;
+    }
+    let dropped = budget::auto_fit(state);
+    state.status = if dropped.is_empty() {
+        "selection already fits the budget".to_string()
+    } else {
+        let names: Vec<String> = dropped
+            .iter()
+            .take(FIT_NAMES)
+            .map(|p| p.display().to_string())
+            .collect();
+        let more = dropped.len().saturating_sub(FIT_NAMES);
+        let rest = if more > 0 {
+            format!(" +{more} more")
+        } else {
+            String::new()
+        };
+        format!(
+            "auto-fit dropped {} file(s): {}{rest}  (all listed under EXCLUDED)",
+            dropped.len(),
+            names.join(", ")
+        )
+    };
+}
+
+/// Select the file (or folder) under the tree cursor together with its
+/// dependency closure in `direction`.
+pub fn include_closure(state: &mut AppState, direction: Direction) {
+    let Some(graph) = &state.graph else {
+        state.status = if state.analysis.is_some() {
+            "still reading imports, try again in a moment".to_string()
+        } else {
+            "no dependency graph: no imports found and no SEMMAP.md graph TD".to_string()
+        };
+        return;
+    };
+    let Some(item) = state.visible_items().get(state.tree_cursor()).cloned() else {
+        return;
+    };
+    let starts =
+        scanner::find_node(&state.tree, &item.path).map_or_else(Vec::new, scanner::all_files);
+    let found = deps::closure(graph, &starts, direction, state.dep_depth);
+    let files: Vec<PathBuf> = starts
+        .iter()
+        .chain(&found)
+        .filter(|p| state.file_sizes.contains_key(*p))
+        .cloned()
+        .collect();
+    selection::add_files(state, &files);
+    let what = match direction {
+        Direction::Dependencies => "dependencies",
+        Direction::Dependents => "dependents",
+    };
+    let depth = state
+        .dep_depth
+        .map_or_else(String::new, |d| format!(" within {d} hop(s)"));
+    state.status = format!("{} + {} {what}{depth}", item.path.display(), found.len());
+}
+
+pub fn cycle_dep_depth(state: &mut AppState) {
+    state.dep_depth = match state.dep_depth {
+        None => DEPTH_PRESETS.first().copied(),
+        Some(cur) => DEPTH_PRESETS.iter().copied().find(|&d| d > cur),
+    };
+    state.status = state.dep_depth.map_or_else(
+        || "dependency depth: unlimited".to_string(),
+        |d| format!("dependency depth: {d}"),
+    );
+}
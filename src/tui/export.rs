@@ -0,0 +1,68 @@
+use crate::{budget, output, packer, types::AppState};
+use anyhow::Result;
+
+/// Per-part limits offered by the `s` key, cycled in order after "off".
+const PART_PRESETS: &[usize] = &[25_000, 50_000, 100_000, 200_000];
+
+pub fn cycle_part_limit(state: &mut AppState) {
+    state.part_limit = match state.part_limit {
+        None => PART_PRESETS.first().copied(),
+        Some(cur) => PART_PRESETS.iter().copied().find(|&p| p > cur),
+    };
+    state.pending_parts.clear();
+    state.status = state.part_limit.map_or_else(
+        || "split off".to_string(),
+        |l| format!("split into parts of {} tok", budget::fmt_tokens(l)),
+    );
+}
+
+/// Build the export, copy part 1 and keep the rest queued for [`copy_next_part`].
+pub fn copy_first_part(state: &mut AppState) -> Result<()> {
+    let parts = packer::build_parts(state, false)?;
+    let n = state.selected_paths().len();
+    let Some(first) = parts.first() else {
+        return Ok(());
+    };
+    output::to_clipboard(first)?;
+    state.status = if parts.len() == 1 {
+        format!("✓ Copied {n} files")
+    } else {
+        format!("✓ Copied part 1/{} ({n} files)  [p]next part", parts.len())
+    };
+    state.pending_parts = parts;
+    state.next_part = 1;
+    Ok(())
+}
+
+pub fn copy_next_part(state: &mut AppState) -> Result<()> {
+    let total = state.pending_parts.len();
+    let Some(part) = state.pending_parts.get(state.next_part) else {
+        state.status = if total == 0 {
+            "no parts queued — [↵] to copy the current selection".to_string()
+        } else {
+            "no more parts — [↵] to copy again".to_string()
+        };
+        return Ok(());
+    };
+    output::to_clipboard(part)?;
+    state.next_part += 1;
+    state.status = format!("✓ Copied part {}/{total}", state.next_part);
+    Ok(())
+}
+
+pub fn save_file(state: &mut AppState) -> Result<()> {
+    let parts = packer::build_parts(state, false)?;
+    let root_name = state
+        .root
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("export");
+    let filename = format!("{root_name}_pakr.{}", state.format.file_extension());
+    let written = output::to_files(&parts, &state.root.join(&filename))?;
+    state.status = if written.len() == 1 {
+        format!("✓ Saved {filename}")
+    } else {
+        format!("✓ Saved {} parts as {root_name}_pakr.partN", written.len())
+    };
+    Ok(())
+}
@@ -0,0 +1,99 @@
+use crate::{fuzzy, history, scanner, selection, types::AppState};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::path::PathBuf;
+
+/// Results kept per query; more than fit on screen, few enough to stay fast.
+const MAX_RESULTS: usize = 200;
+
+/// The fuzzy file finder opened with `a`.
+#[derive(Debug, Clone, Default)]
+pub struct Finder {
+    pub query: String,
+    /// Every file in the tree, captured when the finder opens.
+    pub candidates: Vec<PathBuf>,
+    pub results: Vec<fuzzy::Match>,
+    pub cursor: usize,
+    /// Files marked with Tab, added together on Enter.
+    pub marked: Vec<PathBuf>,
+}
+
+pub fn open(state: &mut AppState) {
+    let mut finder = Finder {
+        candidates: scanner::all_files(&state.tree),
+        ..Finder::default()
+    };
+    refresh(&mut finder);
+    state.finder = Some(finder);
+}
+
+pub fn handle_key(key: KeyEvent, state: &mut AppState) {
+    let Some(finder) = state.finder.as_mut() else {
+        return;
+    };
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    match key.code {
+        KeyCode::Esc => state.finder = None,
+        KeyCode::Enter => history::track(state, add_picked),
+        KeyCode::Up => finder.cursor = finder.cursor.saturating_sub(1),
+        KeyCode::Char('p') if ctrl => finder.cursor = finder.cursor.saturating_sub(1),
+        KeyCode::Down => move_down(finder),
+        KeyCode::Char('n') if ctrl => move_down(finder),
+        KeyCode::Tab => {
+            toggle_mark(finder);
+            move_down(finder);
+        }
+        KeyCode::Backspace => {
+            finder.query.pop();
+            refresh(finder);
+        }
+        KeyCode::Char(c) if !ctrl => {
+            finder.query.push(c);
+            refresh(finder);
+        }
+        _ => {}
+    }
+}
+
+fn refresh(finder: &mut Finder) {
+    finder.results = fuzzy::search(&finder.query, &finder.candidates, MAX_RESULTS);
+    finder.cursor = 0;
+}
+
+fn move_down(finder: &mut Finder) {
+    finder.cursor = (finder.cursor + 1).min(finder.results.len().saturating_sub(1));
+}
+
+fn toggle_mark(finder: &mut Finder) {
+    let Some(path) = finder.results.get(finder.cursor).map(|m| m.path.clone()) else {
+        return;
+    };
+    if let Some(pos) = finder.marked.iter().position(|p| *p == path) {
+        finder.marked.remove(pos);
+    } else {
+        finder.marked.push(path);
+    }
+}
+
+/// Add every marked file, or the one under the cursor when nothing is marked.
+fn add_picked(state: &mut AppState) {
+    let Some(finder) = state.finder.take() else {
+        return;
+    };
+    let picked = if finder.marked.is_empty() {
+        finder
+            .results
+            .get(finder.cursor)
+            .map(|m| vec![m.path.clone()])
+            .unwrap_or_default()
+    } else {
+        finder.marked
+    };
+    for path in &picked {
+        selection::add_file(state, path);
+    }
+    state.status = match picked.as_slice() {
+        [] => "nothing added".to_string(),
+        [one] => format!("added {}", one.display()),
+        many => format!("added {} files", many.len()),
+    };
+}
@@ -0,0 +1,125 @@
+use ratatui::{
+    style::{Color, Style},
+    text::{Line, Span},
+};
+use std::{
+    fs::File,
+    io::{self, Read},
+    path::Path,
+    sync::OnceLock,
+};
+use syntect::{
+    easy::HighlightLines,
+    highlighting::{Theme, ThemeSet},
+    parsing::SyntaxSet,
+};
+
+/// Bytes read for a preview; the rest of a larger file is left out.
+const MAX_BYTES: u64 = 256 * 1024;
+/// Lines past this are summarized instead of shown.
+const MAX_LINES: usize = 2000;
+/// Lines past this are shown without colors, to keep a hover cheap.
+const HIGHLIGHTED: usize = 400;
+const THEME: &str = "base16-ocean.dark";
+
+/// The start of `path` as numbered, highlighted lines; a single grey line
+/// for binaries and unreadable files.
+pub fn file_lines(path: &Path) -> Vec<Line<'static>> {
+    let (bytes, size) = match read_prefix(path) {
+        Ok(read) => read,
+        Err(e) => return vec![note(&format!("cannot read: {e}"))],
+    };
+    if bytes.iter().take(8192).any(|&b| b == 0) {
+        return vec![note(&format!("binary file, {size} bytes"))];
+    }
+    let text = String::from_utf8_lossy(&bytes);
+    let mut lines = highlight(path, &text);
+    if size > MAX_BYTES || text.lines().nth(MAX_LINES).is_some() {
+        lines.push(note(&format!("… file continues, {size} bytes in all")));
+    }
+    lines
+}
+
+/// The first [`MAX_BYTES`] of `path`, with its full size.
+fn read_prefix(path: &Path) -> io::Result<(Vec<u8>, u64)> {
+    let file = File::open(path)?;
+    let size = file.metadata()?.len();
+    let mut bytes = Vec::new();
+    file.take(MAX_BYTES).read_to_end(&mut bytes)?;
+    Ok((bytes, size))
+}
+
+fn highlight(path: &Path, text: &str) -> Vec<Line<'static>> {
+    let syntaxes = syntaxes();
+    let syntax = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .and_then(|ext| syntaxes.find_syntax_by_extension(ext))
+        .or_else(|| {
+            text.lines()
+                .next()
+                .and_then(|l| syntaxes.find_syntax_by_first_line(l))
+        })
+        .unwrap_or_else(|| syntaxes.find_syntax_plain_text());
+    let mut lines = theme().map(|theme| HighlightLines::new(syntax, theme));
+    text.split_inclusive('\n')
+        .take(MAX_LINES)
+        .enumerate()
+        .map(|(i, line)| {
+            let ranges = lines
+                .as_mut()
+                .filter(|_| i < HIGHLIGHTED)
+                .and_then(|l| l.highlight_line(line, syntaxes).ok());
+            match ranges {
+                Some(ranges) => {
+                    numbered(i, ranges.into_iter().map(|(s, t)| (rgb(s.foreground), t)))
+                }
+                None => plain(i, line),
+            }
+        })
+        .collect()
+}
+
+fn numbered<'a>(idx: usize, parts: impl Iterator<Item = (Color, &'a str)>) -> Line<'static> {
+    let mut spans = vec![Span::styled(
+        format!("{:>5} ", idx + 1),
+        Style::default().fg(Color::DarkGray),
+    )];
+    spans.extend(parts.map(|(color, text)| Span::styled(clean(text), Style::default().fg(color))));
+    Line::from(spans)
+}
+
+fn plain(idx: usize, line: &str) -> Line<'static> {
+    numbered(idx, std::iter::once((Color::Reset, line)))
+}
+
+fn note(text: &str) -> Line<'static> {
+    Line::from(Span::styled(
+        format!("  {text}"),
+        Style::default().fg(Color::DarkGray),
+    ))
+}
+
+/// Tabs expand to four spaces and line endings go; the terminal draws neither well.
+fn clean(text: &str) -> String {
+    text.replace('\t', "    ")
+        .trim_end_matches(['\n', '\r'])
+        .to_string()
+}
+
+fn rgb(c: syntect::highlighting::Color) -> Color {
+    Color::Rgb(c.r, c.g, c.b)
+}
+
+fn syntaxes() -> &'static SyntaxSet {
+    static SET: OnceLock<SyntaxSet> = OnceLock::new();
+    SET.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+fn theme() -> Option<&'static Theme> {
+    static THEMES: OnceLock<ThemeSet> = OnceLock::new();
+    THEMES
+        .get_or_init(ThemeSet::load_defaults)
+        .themes
+        .get(THEME)
+}
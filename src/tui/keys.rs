@@ -1,36 +1,78 @@
+use super::{actions, export, finder, panels::include::CatRow, picker, search};
 use crate::{
-    output, packer, scanner,
+    deps::Direction,
+    extensions, history, scanner, selection,
     types::{AppState, Focus},
 };
 use anyhow::Result;
-use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, MouseButton, MouseEvent, MouseEventKind};
-use ratatui::layout::Rect;
-use std::path::PathBuf;
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use std::{collections::HashSet, path::PathBuf};
 
 pub fn handle_key(key: KeyEvent, state: &mut AppState) -> Result<bool> {
     if key.kind != KeyEventKind::Press {
         return Ok(false);
     }
-    if state.input_mode {
-        handle_input_key(key, state);
+    if state.finder.is_some() {
+        finder::handle_key(key, state);
+        state.clamp_cursors();
         return Ok(false);
     }
+    if state.profiles.is_some() {
+        picker::handle_key(key, state);
+        state.clamp_cursors();
+        return Ok(false);
+    }
+    if state.search.as_ref().is_some_and(|s| s.editing) {
+        search::handle_key(key, state);
+        state.clamp_cursors();
+        return Ok(false);
+    }
+    let searching = state.search.is_some() && state.focus == Focus::Tree;
     match key.code {
         KeyCode::Char('q' | 'Q') => return Ok(true),
+        KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            history::redo(state);
+        }
+        KeyCode::Char('u') => history::undo(state),
         KeyCode::Tab => cycle_focus(state),
         KeyCode::Esc => escape_focus(state),
         KeyCode::Up => move_cursor(state, false),
         KeyCode::Down => move_cursor(state, true),
-        KeyCode::Char(' ') => toggle_current(state),
-        KeyCode::Char('n') => exclude_at_cursor(state),
-        KeyCode::Char('d') | KeyCode::Delete => unexclude_current(state),
+        KeyCode::Char(' ') => history::track(state, toggle_current),
+        KeyCode::Char('/') => search::open(state),
+        KeyCode::Char('n') if searching => search::jump(state, true),
+        KeyCode::Char('N') if searching => search::jump(state, false),
+        KeyCode::Char('n') => history::track(state, exclude_at_cursor),
+        KeyCode::Char('o') => history::track(state, pin_full_at_cursor),
+        KeyCode::Char('O') => history::track(state, actions::toggle_outline),
+        KeyCode::Char('H') => history::track(state, actions::toggle_describe),
+        KeyCode::Char('T') => history::track(state, actions::cycle_tag),
+        KeyCode::Char('D') => actions::cycle_diff(state),
+        KeyCode::Char('v') => actions::toggle_preview(state),
+        KeyCode::Char('>') => history::track(state, |s| {
+            actions::include_closure(s, Direction::Dependencies);
+        }),
+        KeyCode::Char('<') => history::track(state, |s| {
+            actions::include_closure(s, Direction::Dependents);
+        }),
+        KeyCode::Char('L') => actions::cycle_dep_depth(state),
+        KeyCode::PageDown => actions::scroll_preview(state, actions::PREVIEW_PAGE),
+        KeyCode::PageUp => actions::scroll_preview(state, -actions::PREVIEW_PAGE),
+        KeyCode::Char('P') => picker::open(state),
+        KeyCode::Char('R') => history::track(state, actions::reset_selection),
+        KeyCode::Char('d') | KeyCode::Delete => history::track(state, unexclude_current),
         KeyCode::Char('e') => {
             state.focus = Focus::Exclude;
         }
-        KeyCode::Char('g') => toggle_gitignore(state),
-        KeyCode::Char('a') => start_input(state),
-        KeyCode::Enter => do_clipboard(state)?,
-        KeyCode::Char('f') => do_file(state)?,
+        KeyCode::Char('g') => history::track(state, toggle_gitignore),
+        KeyCode::Char('a') => finder::open(state),
+        KeyCode::Enter => export::copy_first_part(state)?,
+        KeyCode::Char('p') => export::copy_next_part(state)?,
+        KeyCode::Char('f') => export::save_file(state)?,
+        KeyCode::Char('m') => history::track(state, actions::cycle_format),
+        KeyCode::Char('s') => export::cycle_part_limit(state),
+        KeyCode::Char('b') => actions::cycle_budget(state),
+        KeyCode::Char('t') => history::track(state, actions::fit_budget),
         KeyCode::Left | KeyCode::Right => toggle_expand(state),
         _ => {}
     }
@@ -38,110 +80,6 @@ pub fn handle_key(key: KeyEvent, state: &mut AppState) -> Result<bool> {
     Ok(false)
 }
 
-fn handle_input_key(key: KeyEvent, state: &mut AppState) {
-    match key.code {
-        KeyCode::Esc => {
-            state.input_mode = false;
-            state.input_buffer.clear();
-        }
-        KeyCode::Enter => {
-            let path = PathBuf::from(state.input_buffer.trim());
-            if !path.as_os_str().is_empty() && !state.manual_includes.contains(&path) {
-                state.manual_includes.push(path);
-            }
-            state.input_mode = false;
-            state.input_buffer.clear();
-        }
-        KeyCode::Backspace => {
-            state.input_buffer.pop();
-        }
-        KeyCode::Char(c) => {
-            state.input_buffer.push(c);
-        }
-        _ => {}
-    }
-}
-
-fn start_input(state: &mut AppState) {
-    state.input_mode = true;
-    state.input_buffer.clear();
-}
-
-pub fn handle_mouse(mouse: MouseEvent, state: &mut AppState) -> Result<()> {
-    match mouse.kind {
-        MouseEventKind::Moved => handle_hover(mouse.column, mouse.row, state),
-        MouseEventKind::Down(MouseButton::Left) => handle_click(mouse.column, mouse.row, state),
-        _ => {}
-    }
-    Ok(())
-}
-
-fn handle_hover(col: u16, row: u16, state: &mut AppState) {
-    if in_rect(state.tree_area, col, row) {
-        let idx = tree_hit(row, state.tree_area, state.tree_list_state.offset());
-        let flat = scanner::flatten_visible(&state.tree);
-        state.hover_path = flat.get(idx).map(|i| i.path.clone());
-    } else {
-        state.hover_path = None;
-    }
-}
-
-fn handle_click(col: u16, row: u16, state: &mut AppState) {
-    if in_rect(state.tree_area, col, row) {
-        click_tree(row, state);
-    } else if in_rect(state.cat_area, col, row) {
-        click_cat(row, state);
-    } else if in_rect(state.sub_area, col, row) {
-        click_sub(row, state);
-    }
-    let _ = col;
-}
-
-fn click_tree(row: u16, state: &mut AppState) {
-    let idx = tree_hit(row, state.tree_area, state.tree_list_state.offset());
-    let flat = scanner::flatten_visible(&state.tree);
-    state.focus = Focus::Tree;
-    if let Some(item) = flat.get(idx) {
-        let path = item.path.clone();
-        let is_dir = item.is_dir;
-        state.set_tree_cursor(idx);
-        if is_dir {
-            scanner::toggle_node_expanded(&mut state.tree, &path);
-        }
-    }
-}
-
-fn click_cat(row: u16, state: &mut AppState) {
-    let idx = row_offset(row, state.cat_area);
-    state.focus = Focus::Categories;
-    if idx < state.cat_list_len() {
-        state.cat_cursor = idx;
-    }
-}
-
-fn click_sub(row: u16, state: &mut AppState) {
-    let idx = row_offset(row, state.sub_area);
-    state.focus = Focus::Subsystems;
-    if idx < state.subsystems.len() {
-        state.sub_cursor = idx;
-    }
-}
-
-fn tree_hit(row: u16, area: Rect, scroll: usize) -> usize {
-    row_offset(row, area) + scroll
-}
-
-fn row_offset(row: u16, area: Rect) -> usize {
-    row.saturating_sub(area.y + 1) as usize
-}
-
-fn in_rect(rect: Rect, col: u16, row: u16) -> bool {
-    col >= rect.x
-        && col < rect.x.saturating_add(rect.width)
-        && row >= rect.y
-        && row < rect.y.saturating_add(rect.height)
-}
-
 fn cycle_focus(state: &mut AppState) {
     state.focus = match state.focus {
         Focus::Categories => {
@@ -157,12 +95,14 @@ fn cycle_focus(state: &mut AppState) {
 }
 
 fn escape_focus(state: &mut AppState) {
-    if state.focus == Focus::Exclude {
+    if state.search.is_some() {
+        search::close(state);
+    } else if state.focus == Focus::Exclude {
         state.focus = Focus::Categories;
     }
 }
 
-fn move_cursor(state: &mut AppState, down: bool) {
+pub fn move_cursor(state: &mut AppState, down: bool) {
     match state.focus {
         Focus::Categories => {
             state.cat_cursor = step(state.cat_cursor, down, state.cat_list_len());
@@ -175,7 +115,7 @@ fn move_cursor(state: &mut AppState, down: bool) {
             state.exclude_cursor = step(state.exclude_cursor, down, len);
         }
         Focus::Tree => {
-            let len = scanner::flatten_visible(&state.tree).len();
+            let len = state.visible_items().len();
             let next = step(state.tree_cursor(), down, len);
             state.set_tree_cursor(next);
         }
@@ -201,37 +141,51 @@ fn toggle_current(state: &mut AppState) {
                 sub.enabled = !sub.enabled;
             }
         }
-        Focus::Exclude | Focus::Tree => {}
+        Focus::Tree => toggle_tree_at_cursor(state),
+        Focus::Exclude => {}
     }
 }
 
-fn toggle_cat(state: &mut AppState) {
-    let cursor = state.cat_cursor;
-    if cursor == 0 {
-        state.all_collapsed = !state.all_collapsed;
-        if state.all_collapsed {
-            scanner::collapse_all(&mut state.tree);
-        } else {
-            scanner::expand_all(&mut state.tree);
-        }
-        return;
+fn toggle_tree_at_cursor(state: &mut AppState) {
+    let flat = state.visible_items();
+    if let Some(item) = flat.get(state.tree_cursor()) {
+        selection::toggle(state, &item.path.clone());
     }
-    if cursor == 1 {
-        let target = !state.are_all_selected();
-        for cat in &mut state.categories {
-            cat.enabled = target;
+}
+
+pub fn toggle_cat(state: &mut AppState) {
+    match state.cat_row(state.cat_cursor) {
+        Some(CatRow::CollapseAll) => {
+            state.all_collapsed = !state.all_collapsed;
+            if state.all_collapsed {
+                scanner::collapse_all(&mut state.tree);
+            } else {
+                scanner::expand_all(&mut state.tree);
+            }
         }
-        return;
-    }
-    let base = cursor - 2;
-    if state.has_gitignore {
-        if base == 0 {
-            state.respect_gitignore = !state.respect_gitignore;
-        } else if let Some(cat) = state.categories.get_mut(base - 1) {
-            cat.enabled = !cat.enabled;
+        Some(CatRow::SelectAll) => {
+            let target = !state.are_all_selected();
+            for cat in &mut state.categories {
+                cat.enabled = target;
+            }
         }
-    } else if let Some(cat) = state.categories.get_mut(base) {
-        cat.enabled = !cat.enabled;
+        Some(CatRow::Reset) => actions::reset_selection(state),
+        Some(CatRow::Gitignore) => state.respect_gitignore = !state.respect_gitignore,
+        Some(CatRow::Category(i)) => {
+            if let Some(cat) = state.categories.get_mut(i) {
+                cat.enabled = !cat.enabled;
+            }
+        }
+        Some(CatRow::Extension(i)) => toggle_extension(state, i),
+        Some(CatRow::ExtHeader) | None => {}
+    }
+}
+
+fn toggle_extension(state: &mut AppState, idx: usize) {
+    let selected: HashSet<PathBuf> = state.selected_paths().into_iter().collect();
+    if let Some(group) = state.extensions.get_mut(idx) {
+        let all_selected = group.files.iter().all(|p| selected.contains(p));
+        group.mode = extensions::next_mode(group, all_selected);
     }
 }
 
@@ -242,12 +196,27 @@ fn toggle_gitignore(state: &mut AppState) {
 }
 
 fn exclude_at_cursor(state: &mut AppState) {
-    let flat = scanner::flatten_visible(&state.tree);
+    let flat = state.visible_items();
     if let Some(item) = flat.get(state.tree_cursor()) {
         state.exclude.toggle(item.path.clone());
     }
 }
 
+fn pin_full_at_cursor(state: &mut AppState) {
+    let flat = state.visible_items();
+    let Some(item) = flat.get(state.tree_cursor()) else {
+        return;
+    };
+    let path = item.path.clone();
+    if let Some(pos) = state.full_files.iter().position(|p| p == &path) {
+        state.full_files.remove(pos);
+        state.status = format!("{} back to outline", path.display());
+    } else {
+        state.status = format!("{} kept in full", path.display());
+        state.full_files.push(path);
+    }
+}
+
 fn unexclude_current(state: &mut AppState) {
     if state.focus != Focus::Exclude {
         return;
@@ -262,32 +231,10 @@ fn toggle_expand(state: &mut AppState) {
     if state.focus != Focus::Tree {
         return;
     }
-    let flat = scanner::flatten_visible(&state.tree);
+    let flat = state.visible_items();
     if let Some(item) = flat.get(state.tree_cursor()) {
         if item.is_dir {
             scanner::toggle_node_expanded(&mut state.tree, &item.path.clone());
         }
     }
 }
-
-fn do_clipboard(state: &mut AppState) -> Result<()> {
-    let content = packer::build_export(state)?;
-    let n = state.selected_paths().len();
-    output::to_clipboard(&content)?;
-    state.status = format!("✓ Copied {n} files");
-    Ok(())
-}
-
-fn do_file(state: &mut AppState) -> Result<()> {
-    let content = packer::build_export(state)?;
-    let root_name = state
-        .root
-        .file_name()
-        .and_then(|n| n.to_str())
-        .unwrap_or("export");
-    let filename = format!("{root_name}_pakr.txt");
-    let path = state.root.join(&filename);
-    output::to_file(&content, &path)?;
-    state.status = format!("✓ Saved {filename}");
-    Ok(())
-}
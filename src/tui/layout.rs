@@ -1,21 +1,44 @@
 use crate::types::AppState;
 use ratatui::{
-    layout::{Constraint, Direction, Layout},
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    widgets::Paragraph,
     Frame,
 };
 
 use super::panels;
 
 pub fn render(frame: &mut Frame, state: &mut AppState) {
-    let area = frame.area();
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(1)])
+        .split(frame.area());
+    let mut rows_iter = rows.iter().copied();
+    let (Some(main), Some(status)) = (rows_iter.next(), rows_iter.next()) else {
+        return;
+    };
+
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
-        .split(area);
+        .split(main);
 
     let mut iter = chunks.iter().copied();
     if let (Some(left), Some(right)) = (iter.next(), iter.next()) {
         panels::left::render(frame, left, state);
         panels::right::render(frame, right, state);
     }
+    if let Some(picker) = &state.profiles {
+        panels::profiles::render(frame, main, picker);
+    }
+    if let Some(finder) = &state.finder {
+        panels::finder::render(frame, main, finder);
+    }
+    render_status(frame, status, state);
+}
+
+fn render_status(frame: &mut Frame, area: Rect, state: &AppState) {
+    let widget =
+        Paragraph::new(format!(" {}", state.status)).style(Style::default().fg(Color::Yellow));
+    frame.render_widget(widget, area);
 }
@@ -1,8 +1,15 @@
+pub mod actions;
+pub mod export;
+pub mod finder;
+pub mod highlight;
 pub mod keys;
 pub mod layout;
+pub mod mouse;
 pub mod panels;
+pub mod picker;
+pub mod search;
 
-use crate::types::AppState;
+use crate::{app, session, types::AppState};
 use anyhow::Result;
 use crossterm::{
     event::{self, DisableMouseCapture, EnableMouseCapture, Event},
@@ -13,12 +20,15 @@ use ratatui::{backend::CrosstermBackend, Terminal};
 use std::{io, time::Duration};
 
 pub fn run(mut state: AppState) -> Result<()> {
+    state.tokens.count_in_background();
     enable_raw_mode()?;
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
     let result = event_loop(&mut terminal, &mut state);
+    // Best effort: an unwritable cache only costs the next launch its state.
+    let _ = session::save(&state);
     disable_raw_mode()?;
     execute!(
         terminal.backend_mut(),
@@ -34,17 +44,14 @@ fn event_loop<B: ratatui::backend::Backend>(
     state: &mut AppState,
 ) -> Result<()> {
     loop {
+        app::poll_analysis(state);
         terminal.draw(|f| layout::render(f, state))?;
         if !event::poll(Duration::from_millis(50))? {
             continue;
         }
         match event::read()? {
-            Event::Key(key) => {
-                if keys::handle_key(key, state)? {
-                    return Ok(());
-                }
-            }
-            Event::Mouse(mouse) => keys::handle_mouse(mouse, state)?,
+            Event::Key(key) if keys::handle_key(key, state)? => return Ok(()),
+            Event::Mouse(mouse) => mouse::handle(mouse, state),
             _ => {}
         }
     }
@@ -0,0 +1,169 @@
+use super::{
+    actions, keys,
+    panels::{include::CatRow, summary},
+};
+use crate::{
+    history, scanner, selection,
+    types::{AppState, Focus},
+};
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::Rect;
+use std::time::{Duration, Instant};
+
+/// Two clicks on the same row within this count as a double click.
+const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+/// Rows (or preview lines) moved per wheel notch.
+const WHEEL_STEP: usize = 3;
+
+pub fn handle(mouse: MouseEvent, state: &mut AppState) {
+    let (col, row) = (mouse.column, mouse.row);
+    match mouse.kind {
+        MouseEventKind::Moved => handle_hover(col, row, state),
+        MouseEventKind::Down(MouseButton::Left) => handle_click(col, row, state),
+        MouseEventKind::ScrollDown => handle_wheel(col, row, true, state),
+        MouseEventKind::ScrollUp => handle_wheel(col, row, false, state),
+        _ => {}
+    }
+    state.clamp_cursors();
+}
+
+fn handle_hover(col: u16, row: u16, state: &mut AppState) {
+    if in_rect(state.tree_area, col, row) {
+        let idx = list_hit(row, state.tree_area, state.tree_list_state.offset());
+        state.hover_path = state.visible_items().get(idx).map(|i| i.path.clone());
+    } else {
+        state.hover_path = None;
+    }
+}
+
+fn handle_click(col: u16, row: u16, state: &mut AppState) {
+    let now = Instant::now();
+    let double = state
+        .last_click
+        .is_some_and(|(at, c, r)| r == row && c.abs_diff(col) <= 1 && now - at < DOUBLE_CLICK);
+    // A double click is consumed; a third click starts over.
+    state.last_click = (!double).then_some((now, col, row));
+    if in_rect(state.tree_area, col, row) {
+        click_tree(col, row, double, state);
+    } else if in_rect(state.cat_area, col, row) {
+        click_cat(col, row, state);
+    } else if in_rect(state.sub_area, col, row) {
+        click_sub(col, row, state);
+    } else if in_rect(state.exclude_area, col, row) {
+        click_exclude(row, state);
+    }
+}
+
+/// The check mark toggles, the ▼/▶ icon or a double click folds a folder,
+/// and clicking the file already under the cursor toggles it.
+fn click_tree(col: u16, row: u16, double: bool, state: &mut AppState) {
+    let idx = list_hit(row, state.tree_area, state.tree_list_state.offset());
+    let Some(item) = state.visible_items().get(idx).cloned() else {
+        return;
+    };
+    let again = state.focus == Focus::Tree && state.tree_cursor() == idx;
+    state.focus = Focus::Tree;
+    state.set_tree_cursor(idx);
+    let mark = tree_mark(item.depth, state.tree_area);
+    if near(col, mark) || (again && !item.is_dir) {
+        history::track(state, |s| selection::toggle(s, &item.path));
+    } else if item.is_dir && (near(col, mark.saturating_add(2)) || double) {
+        scanner::toggle_node_expanded(&mut state.tree, &item.path);
+    }
+}
+
+/// Control rows act on a single click; other rows toggle from their
+/// check mark or when clicked a second time.
+fn click_cat(col: u16, row: u16, state: &mut AppState) {
+    let idx = list_hit(row, state.cat_area, state.cat_list_state.offset());
+    if idx >= state.cat_list_len() {
+        return;
+    }
+    let again = state.focus == Focus::Categories && state.cat_cursor == idx;
+    state.focus = Focus::Categories;
+    state.cat_cursor = idx;
+    let button = matches!(
+        state.cat_row(idx),
+        Some(CatRow::CollapseAll | CatRow::SelectAll | CatRow::Reset)
+    );
+    if button || again || on_list_mark(col, state.cat_area) {
+        history::track(state, keys::toggle_cat);
+    }
+}
+
+fn click_sub(col: u16, row: u16, state: &mut AppState) {
+    let idx = list_hit(row, state.sub_area, state.sub_list_state.offset());
+    if idx >= state.subsystems.len() {
+        return;
+    }
+    let again = state.focus == Focus::Subsystems && state.sub_cursor == idx;
+    state.focus = Focus::Subsystems;
+    state.sub_cursor = idx;
+    if again || on_list_mark(col, state.sub_area) {
+        history::track(state, |s| {
+            if let Some(sub) = s.subsystems.get_mut(idx) {
+                sub.enabled = !sub.enabled;
+            }
+        });
+    }
+}
+
+fn click_exclude(row: u16, state: &mut AppState) {
+    let idx =
+        usize::from(row.saturating_sub(state.exclude_area.y)) + summary::exclude_offset(state);
+    if idx < state.exclude.items().len() {
+        state.focus = Focus::Exclude;
+        state.exclude_cursor = idx;
+    }
+}
+
+/// The wheel moves the cursor of whichever list is under the pointer,
+/// or scrolls the preview.
+fn handle_wheel(col: u16, row: u16, down: bool, state: &mut AppState) {
+    if in_rect(state.preview.area, col, row) {
+        let step = isize::try_from(WHEEL_STEP).unwrap_or(1);
+        actions::scroll_preview(state, if down { step } else { -step });
+        return;
+    }
+    let focus = if in_rect(state.tree_area, col, row) {
+        Focus::Tree
+    } else if in_rect(state.cat_area, col, row) {
+        Focus::Categories
+    } else if in_rect(state.sub_area, col, row) {
+        Focus::Subsystems
+    } else if in_rect(state.exclude_area, col, row) {
+        Focus::Exclude
+    } else {
+        return;
+    };
+    state.focus = focus;
+    for _ in 0..WHEEL_STEP {
+        keys::move_cursor(state, down);
+    }
+}
+
+/// Column of a tree row's check mark: after the border and two spaces per level.
+fn tree_mark(depth: usize, area: Rect) -> u16 {
+    let indent = u16::try_from(depth * 2).unwrap_or(u16::MAX);
+    area.x.saturating_add(1).saturating_add(indent)
+}
+
+/// The ` [✓]` box at the start of category and subsystem rows.
+fn on_list_mark(col: u16, area: Rect) -> bool {
+    (area.x.saturating_add(2)..=area.x.saturating_add(4)).contains(&col)
+}
+
+fn near(col: u16, target: u16) -> bool {
+    col.abs_diff(target) <= 1
+}
+
+fn list_hit(row: u16, area: Rect, scroll: usize) -> usize {
+    usize::from(row.saturating_sub(area.y + 1)) + scroll
+}
+
+fn in_rect(rect: Rect, col: u16, row: u16) -> bool {
+    col >= rect.x
+        && col < rect.x.saturating_add(rect.width)
+        && row >= rect.y
+        && row < rect.y.saturating_add(rect.height)
+}
@@ -0,0 +1,77 @@
+use crate::tui::finder::Finder;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+/// Centered overlay: query line on top, ranked matches below.
+pub fn render(frame: &mut Frame, area: Rect, finder: &Finder) {
+    let width = area.width.saturating_mul(4) / 5;
+    let height = area.height.saturating_mul(4) / 5;
+    let rect = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    };
+    frame.render_widget(Clear, rect);
+
+    let title = format!(
+        "ADD FILES  {}/{}  [tab]mark  [↵]add  [esc]close",
+        finder.results.len(),
+        finder.candidates.len()
+    );
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+    let inner = block.inner(rect);
+    frame.render_widget(block, rect);
+
+    let query = Paragraph::new(Line::from(vec![
+        Span::styled("> ", Style::default().fg(Color::Cyan)),
+        Span::raw(finder.query.as_str()),
+        Span::styled("_", Style::default().fg(Color::DarkGray)),
+    ]));
+    frame.render_widget(query, Rect { height: 1, ..inner });
+
+    let items: Vec<ListItem> = finder
+        .results
+        .iter()
+        .map(|m| {
+            let marked = finder.marked.contains(&m.path);
+            ListItem::new(result_line(&m.path.to_string_lossy(), &m.positions, marked))
+        })
+        .collect();
+    let list = List::new(items).highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    let mut list_state = ListState::default();
+    list_state.select((!finder.results.is_empty()).then_some(finder.cursor));
+    let list_area = Rect {
+        y: inner.y.saturating_add(1),
+        height: inner.height.saturating_sub(1),
+        ..inner
+    };
+    frame.render_stateful_widget(list, list_area, &mut list_state);
+}
+
+/// The path with matched characters highlighted, prefixed by a mark column.
+fn result_line(path: &str, positions: &[usize], marked: bool) -> Line<'static> {
+    let hit = Style::default()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+    let mut spans = vec![Span::styled(
+        if marked { " ● " } else { "   " },
+        Style::default().fg(Color::Green),
+    )];
+    spans.extend(path.chars().enumerate().map(|(i, c)| {
+        if positions.contains(&i) {
+            Span::styled(c.to_string(), hit)
+        } else {
+            Span::raw(c.to_string())
+        }
+    }));
+    Line::from(spans)
+}
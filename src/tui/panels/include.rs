@@ -0,0 +1,170 @@
+use super::{dim, hl_style, panel_block};
+use crate::types::{AppState, ExtMode, ExtensionGroup, Focus};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{List, ListItem},
+    Frame,
+};
+use std::{collections::HashSet, hash::BuildHasher, path::PathBuf};
+
+/// What a row of the INCLUDE panel stands for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CatRow {
+    CollapseAll,
+    SelectAll,
+    Reset,
+    Gitignore,
+    Category(usize),
+    ExtHeader,
+    Extension(usize),
+}
+
+impl AppState {
+    /// 3 control rows + optional gitignore row + category rows
+    /// + a file-type header and one row per extension.
+    pub fn cat_list_len(&self) -> usize {
+        let ext_rows = if self.extensions.is_empty() {
+            0
+        } else {
+            1 + self.extensions.len()
+        };
+        3 + self.categories.len() + usize::from(self.has_gitignore) + ext_rows
+    }
+
+    pub fn cat_row(&self, idx: usize) -> Option<CatRow> {
+        let mut rest = match idx {
+            0 => return Some(CatRow::CollapseAll),
+            1 => return Some(CatRow::SelectAll),
+            2 => return Some(CatRow::Reset),
+            n => n - 3,
+        };
+        if self.has_gitignore {
+            if rest == 0 {
+                return Some(CatRow::Gitignore);
+            }
+            rest -= 1;
+        }
+        if rest < self.categories.len() {
+            return Some(CatRow::Category(rest));
+        }
+        rest -= self.categories.len();
+        if self.extensions.is_empty() {
+            return None;
+        }
+        match rest {
+            0 => Some(CatRow::ExtHeader),
+            n if n - 1 < self.extensions.len() => Some(CatRow::Extension(n - 1)),
+            _ => None,
+        }
+    }
+}
+
+pub fn render<S: BuildHasher>(
+    frame: &mut Frame,
+    area: Rect,
+    state: &mut AppState,
+    selected: &HashSet<PathBuf, S>,
+) {
+    let focused = state.focus == Focus::Categories;
+    let mut items: Vec<ListItem> = Vec::new();
+
+    // Row 0: collapse/expand toggle
+    let collapse_label = if state.all_collapsed {
+        "  ▼ EXPAND ALL"
+    } else {
+        "  ▶ COLLAPSE ALL"
+    };
+    items.push(ListItem::new(Line::from(Span::styled(
+        collapse_label,
+        Style::default().fg(Color::DarkGray),
+    ))));
+
+    // Row 1: select/deselect all toggle
+    let select_label = if state.are_all_selected() {
+        "  ☑ DESELECT ALL"
+    } else {
+        "  ☐ SELECT ALL"
+    };
+    items.push(ListItem::new(Line::from(Span::styled(
+        select_label,
+        Style::default().fg(Color::DarkGray),
+    ))));
+
+    // Row 2: back to the startup defaults
+    items.push(ListItem::new(Line::from(Span::styled(
+        "  ↺ RESET SELECTION",
+        Style::default().fg(Color::DarkGray),
+    ))));
+
+    // Optional gitignore row
+    if state.has_gitignore {
+        let gi_check = if state.respect_gitignore {
+            "✓"
+        } else {
+            "✗"
+        };
+        items.push(ListItem::new(Line::from(Span::styled(
+            format!(" [{gi_check}] 🚫 .gitignore"),
+            Style::default().fg(Color::Yellow),
+        ))));
+    }
+
+    for cat in &state.categories {
+        let check = if cat.enabled { "✓" } else { "✗" };
+        let tok = cat.token_estimate(state) / 1000;
+        items.push(ListItem::new(Line::from(format!(
+            " [{check}] {}  ~{tok}k",
+            cat.name()
+        ))));
+    }
+
+    if !state.extensions.is_empty() {
+        items.push(ListItem::new(Line::from(Span::styled(
+            "  ── FILE TYPES ──",
+            dim(),
+        ))));
+        for group in &state.extensions {
+            items.push(ext_item(group, selected, state));
+        }
+    }
+
+    let cursor = state.cat_cursor;
+    state.cat_list_state.select(Some(cursor));
+    let mut block = panel_block("INCLUDE", focused);
+    if !state.semmap_issues.is_empty() {
+        block = block.title(Span::styled(
+            format!(" ⚠ SEMMAP: {} ", state.semmap_issues.len()),
+            Style::default().fg(Color::Black).bg(Color::Yellow),
+        ));
+    }
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(hl_style(focused));
+    frame.render_stateful_widget(list, area, &mut state.cat_list_state);
+}
+
+fn ext_item<S: BuildHasher>(
+    group: &ExtensionGroup,
+    selected: &HashSet<PathBuf, S>,
+    state: &AppState,
+) -> ListItem<'static> {
+    let on = group.files.iter().filter(|p| selected.contains(*p)).count();
+    let (check, style) = match group.mode {
+        ExtMode::Include => ("+", Style::default().fg(Color::Green)),
+        ExtMode::Exclude => ("✗", Style::default().fg(Color::Red)),
+        ExtMode::Inherit if on == 0 => (" ", Style::default()),
+        ExtMode::Inherit if on == group.files.len() => ("✓", Style::default()),
+        ExtMode::Inherit => ("◐", Style::default()),
+    };
+    let tok = group.token_estimate(state) / 1000;
+    ListItem::new(Line::from(Span::styled(
+        format!(
+            " [{check}] {}  {} files  ~{tok}k",
+            group.label(),
+            group.files.len()
+        ),
+        style,
+    )))
+}
@@ -1,2 +1,38 @@
+pub mod finder;
+pub mod include;
 pub mod left;
+pub mod preview;
+pub mod profiles;
 pub mod right;
+pub mod summary;
+
+use ratatui::{
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders},
+};
+
+pub fn panel_block(title: &str, focused: bool) -> Block<'_> {
+    let border_style = if focused {
+        Style::default().fg(Color::Cyan)
+    } else {
+        Style::default()
+    };
+    Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(border_style)
+}
+
+pub fn hl_style(focused: bool) -> Style {
+    if focused {
+        Style::default()
+            .fg(Color::Cyan)
+            .add_modifier(Modifier::REVERSED)
+    } else {
+        Style::default().add_modifier(Modifier::REVERSED)
+    }
+}
+
+pub fn dim() -> Style {
+    Style::default().fg(Color::DarkGray)
+}
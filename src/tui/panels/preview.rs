@@ -0,0 +1,124 @@
+use crate::{semmap::FileNote, tui::highlight, types::AppState};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+/// Files kept highlighted before the cache starts over.
+const CACHED_FILES: usize = 64;
+
+/// The file preview split under the tree, toggled with `v`.
+#[derive(Debug, Default)]
+pub struct Preview {
+    pub open: bool,
+    /// First line shown.
+    pub scroll: usize,
+    pub area: Rect,
+    /// File shown last, so moving to another one starts it at the top.
+    pub shown: Option<PathBuf>,
+    /// Highlighted lines per file and the modification time they were read
+    /// at, so a redraw or a return to a file does not re-read it.
+    pub cache: HashMap<PathBuf, (Option<SystemTime>, Vec<Line<'static>>)>,
+}
+
+impl Preview {
+    fn lines(&mut self, root: &Path, path: &PathBuf) -> &[Line<'static>] {
+        let full = root.join(path);
+        let modified = fs::metadata(&full).and_then(|m| m.modified()).ok();
+        let fresh = self.cache.get(path).is_some_and(|(m, _)| *m == modified);
+        if !fresh {
+            if self.cache.len() >= CACHED_FILES {
+                self.cache.clear();
+            }
+            self.cache
+                .insert(path.clone(), (modified, highlight::file_lines(&full)));
+        }
+        self.cache.get(path).map_or(&[], |(_, lines)| lines)
+    }
+}
+
+/// Contents of the hovered file, or of the file under the tree cursor.
+pub fn render(frame: &mut Frame, area: Rect, state: &mut AppState) {
+    state.preview.area = area;
+    let Some(path) = target(state) else {
+        let hint = Paragraph::new(Line::styled(
+            "  move to a file to preview it",
+            Style::default().fg(Color::DarkGray),
+        ))
+        .block(Block::default().title("FILE").borders(Borders::ALL));
+        frame.render_widget(hint, area);
+        return;
+    };
+    if state.preview.shown.as_ref() != Some(&path) {
+        state.preview.shown = Some(path.clone());
+        state.preview.scroll = 0;
+    }
+    let mut shown = state
+        .graph
+        .as_ref()
+        .and_then(|g| g.note(&path))
+        .map_or_else(Vec::new, note_lines);
+    let wanted = state.preview.scroll;
+    let lines = state.preview.lines(&state.root, &path);
+    let scroll = wanted.min(lines.len().saturating_sub(1));
+    let room = usize::from(area.height).saturating_sub(shown.len());
+    shown.extend(lines.iter().skip(scroll).take(room).cloned());
+    let title = format!(
+        "FILE  {}  {}/{}  [PgUp/PgDn]scroll [v]close",
+        path.display(),
+        (scroll + 1).min(lines.len()),
+        lines.len()
+    );
+    let widget = Paragraph::new(shown).block(Block::default().title(title).borders(Borders::ALL));
+    frame.render_widget(widget, area);
+    state.preview.scroll = scroll;
+}
+
+/// The file's SEMMAP note, pinned above the code: description, then tags
+/// and exports.
+fn note_lines(note: &FileNote) -> Vec<Line<'static>> {
+    let mut out = Vec::new();
+    if !note.description.is_empty() {
+        out.push(Line::styled(
+            format!(" {}", note.description),
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::ITALIC),
+        ));
+    }
+    let mut facts: Vec<String> = note.tags.iter().map(|t| format!("[{t}]")).collect();
+    if !note.exports.is_empty() {
+        facts.push(format!("exports: {}", note.exports.join(", ")));
+    }
+    if !facts.is_empty() {
+        out.push(Line::styled(
+            format!(" {}", facts.join("  ")),
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+    if !out.is_empty() {
+        out.push(Line::default());
+    }
+    out
+}
+
+/// The hovered file, or else the file under the tree cursor.
+pub fn target(state: &AppState) -> Option<PathBuf> {
+    let is_file = |p: &PathBuf| state.file_sizes.contains_key(p);
+    state.hover_path.clone().filter(is_file).or_else(|| {
+        state
+            .visible_items()
+            .get(state.tree_cursor())
+            .map(|item| item.path.clone())
+            .filter(is_file)
+    })
+}
@@ -0,0 +1,60 @@
+use crate::tui::picker::ProfilePicker;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState},
+    Frame,
+};
+
+/// Centered overlay listing saved profiles, with an input row while naming.
+pub fn render(frame: &mut Frame, area: Rect, picker: &ProfilePicker) {
+    let rows = u16::try_from(picker.names.len().max(1)).unwrap_or(u16::MAX);
+    let width = area.width.min(56);
+    let height = rows.saturating_add(3).min(area.height);
+    let rect = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    };
+
+    let mut items: Vec<ListItem> = if picker.names.is_empty() {
+        vec![ListItem::new(Span::styled(
+            "  no profiles yet, [n] saves one",
+            Style::default().fg(Color::DarkGray),
+        ))]
+    } else {
+        picker
+            .names
+            .iter()
+            .map(|n| ListItem::new(format!("  {n}")))
+            .collect()
+    };
+    if let Some(name) = &picker.naming {
+        items.push(ListItem::new(Line::from(Span::styled(
+            format!(" new: {name}_"),
+            Style::default().fg(Color::Yellow),
+        ))));
+    }
+
+    let title = if picker.naming.is_some() {
+        "SAVE PROFILE  [↵]save  [esc]cancel"
+    } else {
+        "PROFILES  [↵]load [s]save [n]new [d]del"
+    };
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan)),
+        )
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    let mut list_state = ListState::default();
+    if picker.naming.is_none() && !picker.names.is_empty() {
+        list_state.select(Some(picker.cursor));
+    }
+    frame.render_widget(Clear, rect);
+    frame.render_stateful_widget(list, rect, &mut list_state);
+}
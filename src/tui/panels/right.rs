@@ -1,37 +1,42 @@
+use super::preview;
 use crate::{
-    scanner,
+    outline, scanner,
+    selection::{self, CheckState},
     types::{AppState, Focus},
 };
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, Paragraph},
+    widgets::{Block, Borders, List, ListItem},
     Frame,
 };
-use std::{collections::HashSet, path::PathBuf};
+use std::{collections::HashSet, ops::Range, path::PathBuf};
 
+/// The tree, with the file preview split underneath when it is open.
 pub fn render(frame: &mut Frame, area: Rect, state: &mut AppState) {
-    if state.input_mode {
-        let chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([Constraint::Min(3), Constraint::Length(3)])
-            .split(area);
-        let mut iter = chunks.iter().copied();
-        if let (Some(tree_area), Some(input_area)) = (iter.next(), iter.next()) {
-            state.tree_area = tree_area;
-            render_tree(frame, tree_area, state);
-            render_input_bar(frame, input_area, state);
-        }
-    } else {
+    if !state.preview.open {
         state.tree_area = area;
+        state.preview.area = Rect::default();
         render_tree(frame, area, state);
+        return;
+    }
+    let halves = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
+        .split(area);
+    let mut iter = halves.iter().copied();
+    if let (Some(tree), Some(file)) = (iter.next(), iter.next()) {
+        state.tree_area = tree;
+        render_tree(frame, tree, state);
+        preview::render(frame, file, state);
     }
 }
 
 fn render_tree(frame: &mut Frame, area: Rect, state: &mut AppState) {
     let selected: HashSet<PathBuf> = state.selected_paths().into_iter().collect();
-    let flat = scanner::flatten_visible(&state.tree);
+    let dirs = selection::dir_states(state, &selected);
+    let flat = state.visible_items();
     let hover = state.hover_path.clone();
     let focused = state.focus == Focus::Tree;
 
@@ -39,17 +44,27 @@ fn render_tree(frame: &mut Frame, area: Rect, state: &mut AppState) {
         .iter()
         .map(|item| {
             let is_excluded = state.exclude.contains(&item.path);
-            let is_selected = !is_excluded && selected.contains(&item.path);
+            let check = if item.is_dir {
+                dirs.get(&item.path)
+                    .copied()
+                    .unwrap_or(CheckState::Unchecked)
+            } else if selected.contains(&item.path) {
+                CheckState::Checked
+            } else {
+                CheckState::Unchecked
+            };
             let is_hovered = hover.as_deref() == Some(item.path.as_path());
             let tok = if item.is_dir {
                 None
             } else {
-                state
-                    .file_sizes
-                    .get(&item.path)
-                    .map(|&s| usize::try_from(s / 3).unwrap_or(usize::MAX / 3))
+                state.file_tokens(&item.path)
             };
-            make_item(item, is_selected, is_excluded, is_hovered, tok)
+            let outlined = !item.is_dir
+                && check == CheckState::Checked
+                && state.wants_outline(&item.path)
+                && outline::supports(&item.path);
+            let hit = state.search.as_ref().and_then(|s| s.hit(item));
+            make_item(item, check, is_excluded, is_hovered, tok, outlined, hit)
         })
         .collect();
 
@@ -61,32 +76,53 @@ fn render_tree(frame: &mut Frame, area: Rect, state: &mut AppState) {
 
     let cursor = state.tree_cursor();
     state.tree_list_state.select(Some(cursor));
+    let mut block = Block::default()
+        .title(title(state))
+        .borders(Borders::ALL)
+        .border_style(border_style);
+    if let Some(note) = hover_note(state) {
+        block = block.title_bottom(Span::styled(
+            format!(" {note} "),
+            Style::default().fg(Color::Cyan),
+        ));
+    }
     let list = List::new(items)
-        .block(
-            Block::default()
-                .title("PREVIEW TREE")
-                .borders(Borders::ALL)
-                .border_style(border_style),
-        )
+        .block(block)
         .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
     frame.render_stateful_widget(list, area, &mut state.tree_list_state);
 }
 
+/// SEMMAP note of the hovered or cursor file, shown on the tree's bottom
+/// border while the preview (which shows it in full) is closed.
+fn hover_note(state: &AppState) -> Option<String> {
+    if state.preview.open {
+        return None;
+    }
+    let path = preview::target(state)?;
+    let summary = state.graph.as_ref()?.note(&path)?.summary();
+    Some(summary).filter(|s| !s.is_empty())
+}
+
+fn title(state: &AppState) -> String {
+    match &state.search {
+        Some(search) if search.editing => format!("PREVIEW TREE  /{}_", search.query),
+        Some(search) => format!("PREVIEW TREE  /{}  [n/N]jump [esc]clear", search.query),
+        None => "PREVIEW TREE".to_string(),
+    }
+}
+
 fn make_item(
     item: &scanner::FlatItem,
-    is_selected: bool,
+    check: CheckState,
     is_excluded: bool,
     is_hovered: bool,
     tok: Option<usize>,
+    outlined: bool,
+    hit: Option<Range<usize>>,
 ) -> ListItem<'static> {
     let indent = "  ".repeat(item.depth);
-    let status = if is_excluded {
-        "✗"
-    } else if is_selected {
-        "✓"
-    } else {
-        " "
-    };
+    let is_selected = !is_excluded && check != CheckState::Unchecked;
+    let status = if is_excluded { "✗" } else { check.symbol() };
     let icon = if item.is_dir {
         if item.expanded {
             "▼ "
@@ -103,7 +139,9 @@ fn make_item(
             format!("  ~{t}")
         }
     });
-    let label = format!("{indent}{status} {icon}{}{tok_str}", item.name);
+    let mark = if outlined { "  ⋯sig" } else { "" };
+    let lead = format!("{indent}{status} {icon}");
+    let tail = format!("{tok_str}{mark}");
 
     let color = if is_excluded {
         Color::DarkGray
@@ -115,18 +153,32 @@ fn make_item(
         Color::DarkGray
     };
 
-    ListItem::new(Line::from(Span::styled(label, Style::default().fg(color))))
+    let style = Style::default().fg(color);
+    let mut spans = vec![Span::styled(lead, style)];
+    spans.extend(name_spans(&item.name, hit, style));
+    spans.push(Span::styled(tail, style));
+    ListItem::new(Line::from(spans))
 }
 
-fn render_input_bar(frame: &mut Frame, area: Rect, state: &AppState) {
-    let text = format!(" {}_", state.input_buffer);
-    let widget = Paragraph::new(text)
-        .style(Style::default().fg(Color::Yellow))
-        .block(
-            Block::default()
-                .title("ADD FILE (relative path)  [↵]confirm  [esc]cancel")
-                .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Yellow)),
-        );
-    frame.render_widget(widget, area);
+/// The name with the search match picked out.
+fn name_spans(name: &str, hit: Option<Range<usize>>, style: Style) -> Vec<Span<'static>> {
+    let parts = hit.and_then(|r| {
+        Some((
+            name.get(..r.start)?,
+            name.get(r.clone())?,
+            name.get(r.end..)?,
+        ))
+    });
+    let Some((before, matched, after)) = parts else {
+        return vec![Span::styled(name.to_string(), style)];
+    };
+    let highlight = Style::default()
+        .fg(Color::Black)
+        .bg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+    vec![
+        Span::styled(before.to_string(), style),
+        Span::styled(matched.to_string(), highlight),
+        Span::styled(after.to_string(), style),
+    ]
 }
@@ -0,0 +1,203 @@
+use super::dim;
+use crate::{
+    budget,
+    types::{AppState, Focus},
+};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span, Text},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+/// Rows of the EXCLUDED list shown at once.
+const EXCLUDE_ROWS: usize = 5;
+
+/// First excluded path shown, scrolled just far enough to keep the cursor in view.
+pub fn exclude_offset(state: &AppState) -> usize {
+    state.exclude_cursor.saturating_sub(EXCLUDE_ROWS - 1)
+}
+
+/// Rows the panel needs: the fixed lines plus the EXCLUDED and ADDED
+/// MANUALLY lists, the budget gauge and the SEMMAP line when shown.
+pub fn height(state: &AppState) -> u16 {
+    let excl_rows = u16::try_from(state.exclude.items().len().min(EXCLUDE_ROWS)).unwrap_or(5);
+    let manual_rows = u16::try_from(state.added().count().min(3)).unwrap_or(3);
+    13 + excl_rows + manual_rows + u16::from(state.budget.is_some()) + u16::from(state.has_semmap)
+}
+
+/// Also records where the excluded paths were drawn, for mouse clicks.
+pub fn render(
+    frame: &mut Frame,
+    area: Rect,
+    state: &mut AppState,
+    sel_count: usize,
+    sel_bytes: u64,
+    sel_tokens: usize,
+) {
+    let focused_excl = state.focus == Focus::Exclude;
+    let mut lines: Vec<Line> = vec![Line::from(format!(
+        " {} files  {}  ~{}k tok",
+        sel_count,
+        fmt_bytes(sel_bytes),
+        sel_tokens / 1000
+    ))];
+    if let Some(limit) = state.budget {
+        lines.push(budget_line(sel_tokens, limit));
+    }
+    lines.push(Line::default());
+    lines.push(Line::from(Span::styled(
+        " EXCLUDED  [e]focus  [d]remove",
+        dim(),
+    )));
+
+    let first_row = lines.len();
+    let shown = push_excluded(&mut lines, state);
+    state.exclude_area = rows_in(area, first_row, shown);
+
+    if state.added().next().is_some() {
+        lines.push(Line::default());
+        lines.push(Line::from(Span::styled(" ADDED MANUALLY", dim())));
+        for path in state.added().take(3) {
+            let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("?");
+            lines.push(Line::from(Span::styled(
+                format!("  + {name}"),
+                Style::default().fg(Color::Green),
+            )));
+        }
+    }
+
+    lines.push(Line::default());
+    let gi_hint = if state.has_gitignore {
+        "  [g]gitignore"
+    } else {
+        ""
+    };
+    lines.push(Line::from(Span::styled(
+        format!(" [↑↓]nav  [spc]toggle  [tab]panel{gi_hint}"),
+        dim(),
+    )));
+    lines.push(Line::from(Span::styled(
+        " [n]excl  [a]add  [↵]copy  [f]save  [q]quit",
+        dim(),
+    )));
+    let split = state
+        .part_limit
+        .map_or_else(|| "off".to_string(), budget::fmt_tokens);
+    lines.push(Line::from(Span::styled(
+        format!(
+            " [m]format: {}  [s]split: {split}  [p]next part",
+            state.format.label()
+        ),
+        dim(),
+    )));
+    lines.push(Line::from(Span::styled(
+        format!(" [b]budget  [t]fit  tok: {}", state.tokens.name()),
+        dim(),
+    )));
+
+    let outline = if state.outline { "on" } else { "off" };
+    lines.push(Line::from(Span::styled(
+        format!(
+            " [O]outline: {outline}  [D]diff: {}  [P]profiles",
+            state.diff.mode.label()
+        ),
+        dim(),
+    )));
+    if state.has_semmap {
+        let tag = state
+            .tag_filter
+            .as_ref()
+            .map_or_else(|| "all".to_string(), |t| format!("[{t}]"));
+        let notes = if state.describe { "on" } else { "off" };
+        lines.push(Line::from(Span::styled(
+            format!(" [T]tag: {tag}  [H]notes: {notes}"),
+            dim(),
+        )));
+    }
+
+    let border_style = if focused_excl {
+        Style::default().fg(Color::Cyan)
+    } else {
+        Style::default()
+    };
+    let widget = Paragraph::new(Text::from(lines)).block(
+        Block::default()
+            .title("SUMMARY")
+            .borders(Borders::ALL)
+            .border_style(border_style),
+    );
+    frame.render_widget(widget, area);
+}
+
+/// The EXCLUDED rows in view, or a placeholder; returns how many paths it drew.
+fn push_excluded(lines: &mut Vec<Line>, state: &AppState) -> usize {
+    if state.exclude.is_empty() {
+        lines.push(Line::from(Span::styled("  nothing excluded", dim())));
+        return 0;
+    }
+    let focused = state.focus == Focus::Exclude;
+    let items = state.exclude.items().iter().enumerate();
+    let mut shown = 0;
+    for (i, path) in items.skip(exclude_offset(state)).take(EXCLUDE_ROWS) {
+        let style = if focused && i == state.exclude_cursor {
+            Style::default().add_modifier(Modifier::REVERSED)
+        } else {
+            Style::default().fg(Color::Red)
+        };
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("?");
+        lines.push(Line::from(Span::styled(format!("  ✗ {name}"), style)));
+        shown += 1;
+    }
+    shown
+}
+
+/// `count` text rows starting at line `first` inside a bordered `area`,
+/// clipped to what the border leaves visible.
+fn rows_in(area: Rect, first: usize, count: usize) -> Rect {
+    let inner = area.inner(ratatui::layout::Margin::new(1, 1));
+    let y = inner
+        .y
+        .saturating_add(u16::try_from(first).unwrap_or(u16::MAX))
+        .min(inner.bottom());
+    let height = u16::try_from(count)
+        .unwrap_or(u16::MAX)
+        .min(inner.bottom() - y);
+    Rect { y, height, ..inner }
+}
+
+fn budget_line(tokens: usize, limit: usize) -> Line<'static> {
+    const WIDTH: usize = 16;
+    let filled = (tokens.saturating_mul(WIDTH) / limit.max(1)).min(WIDTH);
+    let color = if tokens > limit {
+        Color::Red
+    } else {
+        Color::Green
+    };
+    Line::from(vec![
+        Span::raw(" "),
+        Span::styled("█".repeat(filled), Style::default().fg(color)),
+        Span::styled("░".repeat(WIDTH - filled), dim()),
+        Span::styled(
+            format!(
+                " {}% of {}",
+                tokens.saturating_mul(100) / limit.max(1),
+                budget::fmt_tokens(limit)
+            ),
+            Style::default().fg(color),
+        ),
+    ])
+}
+
+fn fmt_bytes(bytes: u64) -> String {
+    const KB: u64 = 1024;
+    const MB: u64 = 1024 * 1024;
+    if bytes < KB {
+        format!("{bytes} B")
+    } else if bytes < MB {
+        format!("{} KB", bytes / KB)
+    } else {
+        format!("{} MB", bytes / MB)
+    }
+}
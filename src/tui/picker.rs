@@ -0,0 +1,109 @@
+use crate::{history, profile, types::AppState};
+use crossterm::event::{KeyCode, KeyEvent};
+use std::path::PathBuf;
+
+/// The profile picker overlay opened with `P`.
+#[derive(Debug, Clone, Default)]
+pub struct ProfilePicker {
+    pub names: Vec<String>,
+    pub cursor: usize,
+    /// Name being typed for a new profile.
+    pub naming: Option<String>,
+}
+
+pub fn open(state: &mut AppState) {
+    state.profiles = Some(ProfilePicker {
+        names: profile::list(&state.root),
+        cursor: 0,
+        naming: None,
+    });
+    state.status = "profiles: [↵]load  [s]save  [n]new  [d]delete  [esc]close".to_string();
+}
+
+pub fn handle_key(key: KeyEvent, state: &mut AppState) {
+    let Some(picker) = state.profiles.as_mut() else {
+        return;
+    };
+    if let Some(name) = picker.naming.as_mut() {
+        match key.code {
+            KeyCode::Esc => picker.naming = None,
+            KeyCode::Enter => {
+                let name = name.trim().to_string();
+                save(state, &name);
+            }
+            KeyCode::Backspace => {
+                name.pop();
+            }
+            KeyCode::Char(c) => name.push(c),
+            _ => {}
+        }
+        return;
+    }
+    let selected = picker.names.get(picker.cursor).cloned();
+    match (key.code, selected) {
+        (KeyCode::Esc | KeyCode::Char('P' | 'q'), _) => state.profiles = None,
+        (KeyCode::Up, _) => picker.cursor = picker.cursor.saturating_sub(1),
+        (KeyCode::Down, _) => {
+            picker.cursor = (picker.cursor + 1).min(picker.names.len().saturating_sub(1));
+        }
+        (KeyCode::Char('n'), _) | (KeyCode::Char('s'), None) => {
+            picker.naming = Some(String::new());
+        }
+        (KeyCode::Enter, Some(name)) => history::track(state, |s| load(s, &name)),
+        (KeyCode::Char('s'), Some(name)) => save(state, &name),
+        (KeyCode::Char('d'), Some(name)) => delete(state, &name),
+        _ => {}
+    }
+}
+
+fn load(state: &mut AppState, name: &str) {
+    state.status = match profile::load(state, name) {
+        Ok(missing) if missing.is_empty() => format!("loaded profile '{name}'"),
+        Ok(missing) => format!(
+            "loaded profile '{name}'; dropped {} missing: {}",
+            missing.len(),
+            join(&missing)
+        ),
+        Err(e) => format!("{e:#}"),
+    };
+    state.profiles = None;
+}
+
+fn save(state: &mut AppState, name: &str) {
+    state.status = match profile::save(state, name) {
+        Ok(path) => format!("saved profile '{name}' to {}", path.display()),
+        Err(e) => format!("{e:#}"),
+    };
+    reopen(state, name);
+}
+
+fn delete(state: &mut AppState, name: &str) {
+    state.status = match profile::delete(&state.root, name) {
+        Ok(()) => format!("deleted profile '{name}'"),
+        Err(e) => format!("{e:#}"),
+    };
+    reopen(state, name);
+}
+
+/// Refresh the list after a change, keeping the cursor near `name`.
+fn reopen(state: &mut AppState, name: &str) {
+    let names = profile::list(&state.root);
+    let cursor = names
+        .iter()
+        .position(|n| n.as_str() >= name)
+        .unwrap_or(names.len())
+        .min(names.len().saturating_sub(1));
+    state.profiles = Some(ProfilePicker {
+        names,
+        cursor,
+        naming: None,
+    });
+}
+
+fn join(paths: &[PathBuf]) -> String {
+    paths
+        .iter()
+        .map(|p| p.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,163 @@
+use crate::{
+    scanner,
+    types::{AppState, Focus},
+};
+use crossterm::event::{KeyCode, KeyEvent};
+
+/// The tree filter opened with `/` in the PREVIEW TREE.
+#[derive(Debug, Clone, Default)]
+pub struct TreeSearch {
+    pub query: String,
+    /// Still typing; `Enter` keeps the filter and hands `n`/`N` back to hit jumping.
+    pub editing: bool,
+}
+
+impl TreeSearch {
+    /// Byte range of the match within `item.name`. A query containing `/`
+    /// is matched against the whole path and highlights the name when it hits.
+    pub fn hit(&self, item: &scanner::FlatItem) -> Option<std::ops::Range<usize>> {
+        let query: Vec<char> = self.query.chars().flat_map(char::to_lowercase).collect();
+        if query.is_empty() {
+            return None;
+        }
+        if let Some(range) = find_folded(&item.name, &query) {
+            return Some(range);
+        }
+        let path = item.path.to_string_lossy();
+        (self.query.contains('/') && find_folded(&path, &query).is_some())
+            .then_some(0..item.name.len())
+    }
+}
+
+/// First case-insensitive occurrence of the lowercased `query` in `text`,
+/// as a byte range of `text` itself, so it can slice the original.
+fn find_folded(text: &str, query: &[char]) -> Option<std::ops::Range<usize>> {
+    text.char_indices().find_map(|(start, _)| {
+        let mut wanted = query.iter();
+        let mut next = wanted.next();
+        for (i, c) in text.get(start..)?.char_indices() {
+            for lower in c.to_lowercase() {
+                if next != Some(&lower) {
+                    return None;
+                }
+                next = wanted.next();
+            }
+            if next.is_none() {
+                return Some(start..start + i + c.len_utf8());
+            }
+        }
+        None
+    })
+}
+
+pub fn open(state: &mut AppState) {
+    state.focus = Focus::Tree;
+    state.search = Some(TreeSearch {
+        query: String::new(),
+        editing: true,
+    });
+}
+
+/// Keys while typing the query; every edit re-filters and jumps to the first hit.
+pub fn handle_key(key: KeyEvent, state: &mut AppState) {
+    let Some(search) = state.search.as_mut() else {
+        return;
+    };
+    match key.code {
+        KeyCode::Esc => close(state),
+        KeyCode::Enter => {
+            search.editing = false;
+            if search.query.is_empty() {
+                close(state);
+            }
+        }
+        KeyCode::Backspace => {
+            search.query.pop();
+            first_hit(state);
+        }
+        KeyCode::Char(c) => {
+            search.query.push(c);
+            first_hit(state);
+        }
+        _ => {}
+    }
+    report(state);
+}
+
+/// Move to the next (or previous) hit, wrapping around the ends.
+pub fn jump(state: &mut AppState, forward: bool) {
+    let hits = hit_rows(state);
+    let cursor = state.tree_cursor();
+    let next = if forward {
+        hits.iter().find(|&&i| i > cursor).or_else(|| hits.first())
+    } else {
+        hits.iter()
+            .rev()
+            .find(|&&i| i < cursor)
+            .or_else(|| hits.last())
+    };
+    if let Some(&idx) = next {
+        state.set_tree_cursor(idx);
+    }
+    report(state);
+}
+
+/// Drop the filter, keeping the cursor on the same file in the full tree.
+pub fn close(state: &mut AppState) {
+    let current = state
+        .visible_items()
+        .get(state.tree_cursor())
+        .map(|item| item.path.clone());
+    state.search = None;
+    state.status.clear();
+    let Some(path) = current else {
+        return;
+    };
+    scanner::reveal(&mut state.tree, &path);
+    if let Some(idx) = state.visible_items().iter().position(|i| i.path == path) {
+        state.set_tree_cursor(idx);
+    }
+}
+
+fn first_hit(state: &mut AppState) {
+    let first = hit_rows(state).first().copied().unwrap_or(0);
+    state.set_tree_cursor(first);
+}
+
+/// Row indices of the matches themselves, not the folders shown around them.
+fn hit_rows(state: &AppState) -> Vec<usize> {
+    let Some(search) = &state.search else {
+        return Vec::new();
+    };
+    state
+        .visible_items()
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| search.hit(item).is_some())
+        .map(|(i, _)| i)
+        .collect()
+}
+
+fn report(state: &mut AppState) {
+    let Some(search) = &state.search else {
+        return;
+    };
+    let hits = hit_rows(state);
+    let at = hits
+        .iter()
+        .position(|&i| i == state.tree_cursor())
+        .map_or_else(String::new, |p| format!("{}/", p + 1));
+    state.status = if search.editing {
+        format!(
+            "/{}  {} match(es)  [↵]keep  [esc]clear",
+            search.query,
+            hits.len()
+        )
+    } else {
+        format!(
+            "/{}  {at}{}  [n/N]next/prev  [esc]clear",
+            search.query,
+            hits.len()
+        )
+    };
+}
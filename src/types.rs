@@ -1,30 +1,36 @@
+use crate::{
+    analyzer,
+    diff::DiffOptions,
+    format::ExportFormat,
+    history::History,
+    path_list::PathList,
+    scanner,
+    semmap::{check::Issue, SemmapData},
+    snapshot::Snapshot,
+    tokens::TokenCounter,
+    tui::{finder::Finder, panels::preview::Preview, picker::ProfilePicker, search::TreeSearch},
+};
 use ratatui::{layout::Rect, widgets::ListState};
+use serde::{Deserialize, Serialize};
 use std::{
     collections::{HashMap, HashSet},
     path::{Path, PathBuf},
+    time::Instant,
 };
 
-#[derive(Default)]
-pub struct GitignoreFilter(Option<ignore::gitignore::Gitignore>);
-
-impl std::fmt::Debug for GitignoreFilter {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "GitignoreFilter({})", self.0.is_some())
-    }
-}
+/// Files the ignore rules hide; `None` when nothing in the project is ignored.
+#[derive(Debug, Default)]
+pub struct GitignoreFilter(Option<HashSet<PathBuf>>);
 
 impl GitignoreFilter {
-    pub fn new(gi: ignore::gitignore::Gitignore) -> Self {
-        Self(Some(gi))
+    pub fn new(ignored: HashSet<PathBuf>) -> Self {
+        Self((!ignored.is_empty()).then_some(ignored))
     }
     pub fn has_filter(&self) -> bool {
         self.0.is_some()
     }
-    pub fn is_ignored(&self, root: &Path, rel: &Path) -> bool {
-        let Some(gi) = &self.0 else {
-            return false;
-        };
-        gi.matched(root.join(rel), false).is_ignore()
+    pub fn is_ignored(&self, rel: &Path) -> bool {
+        self.0.as_ref().is_some_and(|set| set.contains(rel))
     }
 }
 
@@ -40,18 +46,34 @@ pub struct FileNode {
 
 #[derive(Debug, Clone)]
 pub enum CategoryKind {
-    SemmapLayer { index: u8, label: String },
+    SemmapLayer {
+        index: u8,
+        label: String,
+    },
     Source,
     Config,
     Docs,
     Build,
     Assets,
+    /// Files reported by `git status`, or changed since a revision.
+    Git {
+        set: GitSet,
+        label: String,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GitSet {
+    Modified,
+    Staged,
+    Untracked,
+    Since,
 }
 
 impl CategoryKind {
     pub fn display_name(&self) -> &str {
         match self {
-            Self::SemmapLayer { label, .. } => label.as_str(),
+            Self::SemmapLayer { label, .. } | Self::Git { label, .. } => label.as_str(),
             Self::Source => "Source",
             Self::Config => "Config",
             Self::Docs => "Docs",
@@ -72,12 +94,8 @@ impl Category {
     pub fn name(&self) -> &str {
         self.kind.display_name()
     }
-    pub fn token_estimate(&self, sizes: &HashMap<PathBuf, u64>) -> usize {
-        self.files
-            .iter()
-            .filter_map(|p| sizes.get(p))
-            .map(|&s| usize::try_from(s / 3).unwrap_or(usize::MAX / 3))
-            .sum()
+    pub fn token_estimate(&self, state: &AppState) -> usize {
+        state.tokens_for(&self.files)
     }
 }
 
@@ -89,38 +107,43 @@ pub struct Subsystem {
 }
 
 impl Subsystem {
-    pub fn token_estimate(&self, sizes: &HashMap<PathBuf, u64>) -> usize {
-        self.files
-            .iter()
-            .filter_map(|p| sizes.get(p))
-            .map(|&s| usize::try_from(s / 3).unwrap_or(usize::MAX / 3))
-            .sum()
+    pub fn token_estimate(&self, state: &AppState) -> usize {
+        state.tokens_for(&self.files)
     }
 }
 
-#[derive(Debug, Clone, Default)]
-pub struct ExcludeList(Vec<PathBuf>);
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExtMode {
+    /// Follow whatever categories and subsystems decide.
+    Inherit,
+    /// Force every file with this extension into the selection.
+    Include,
+    /// Force every file with this extension out of the selection.
+    Exclude,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExtensionGroup {
+    /// Lowercase extension without the dot; empty for extensionless files.
+    pub ext: String,
+    pub files: Vec<PathBuf>,
+    pub mode: ExtMode,
+}
 
-impl ExcludeList {
-    pub fn toggle(&mut self, path: PathBuf) {
-        if let Some(pos) = self.0.iter().position(|p| p == &path) {
-            self.0.remove(pos);
+impl ExtensionGroup {
+    pub fn label(&self) -> String {
+        if self.ext.is_empty() {
+            "(no extension)".to_string()
         } else {
-            self.0.push(path);
+            format!("*.{}", self.ext)
         }
     }
-    pub fn contains(&self, path: &Path) -> bool {
-        self.0.iter().any(|p| p.as_path() == path)
-    }
-    pub fn items(&self) -> &[PathBuf] {
-        &self.0
-    }
-    pub fn is_empty(&self) -> bool {
-        self.0.is_empty()
+    pub fn token_estimate(&self, state: &AppState) -> usize {
+        state.tokens_for(&self.files)
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Focus {
     Categories,
     Subsystems,
@@ -133,28 +156,69 @@ pub struct AppState {
     pub root: PathBuf,
     pub tree: FileNode,
     pub file_sizes: HashMap<PathBuf, u64>,
+    pub tokens: TokenCounter,
     pub categories: Vec<Category>,
     pub subsystems: Vec<Subsystem>,
-    pub exclude: ExcludeList,
+    pub extensions: Vec<ExtensionGroup>,
+    pub exclude: PathList,
+    /// Files and folders checked in the tree. They join the category
+    /// selection and still pass through the subsystem, tag and type filters.
+    pub include: PathList,
+    /// Files picked by name, added even when a filter would drop them.
     pub manual_includes: Vec<PathBuf>,
     pub has_semmap: bool,
+    /// What `pakr semmap check` would report, shown as a badge on INCLUDE.
+    pub semmap_issues: Vec<Issue>,
+    /// Import edges between files, followed by the `>` and `<` tree actions.
+    pub graph: Option<SemmapData>,
+    /// Import analysis still running, when there is no SEMMAP to read.
+    pub analysis: Option<analyzer::Pending>,
+    /// Hop limit for `>` and `<`; `None` follows the whole chain.
+    pub dep_depth: Option<usize>,
+    /// Keep only files whose SEMMAP entry carries this tag (`T` cycles it).
+    pub tag_filter: Option<String>,
     pub has_gitignore: bool,
     pub respect_gitignore: bool,
     pub gitignore_filter: GitignoreFilter,
+    pub format: ExportFormat,
+    /// Token budget for the selection; `None` disables the SUMMARY gauge.
+    pub budget: Option<usize>,
+    /// Per-message token limit; exports above it are cut into numbered parts.
+    pub part_limit: Option<usize>,
+    /// Export signatures only, except for files pinned in `full_files`.
+    pub outline: bool,
+    pub full_files: Vec<PathBuf>,
+    /// Put each file's SEMMAP description in the export, above its content.
+    pub describe: bool,
+    /// Whether and how a `git diff` section is added to the export.
+    pub diff: DiffOptions,
+    /// Parts of the last clipboard export and the index of the next one to copy.
+    pub pending_parts: Vec<String>,
+    pub next_part: usize,
     pub all_collapsed: bool,
     pub focus: Focus,
     pub cat_cursor: usize,
     pub sub_cursor: usize,
     pub exclude_cursor: usize,
     pub hover_path: Option<PathBuf>,
-    pub input_mode: bool,
-    pub input_buffer: String,
+    /// When and where the last left click landed, for spotting double clicks.
+    pub last_click: Option<(Instant, u16, u16)>,
+    pub finder: Option<Finder>,
+    pub search: Option<TreeSearch>,
+    pub preview: Preview,
+    pub profiles: Option<ProfilePicker>,
+    /// The selection as first computed from SEMMAP/heuristics and config,
+    /// restored by "Reset selection".
+    pub defaults: Snapshot,
+    pub history: History,
     pub cat_list_state: ListState,
     pub sub_list_state: ListState,
     pub tree_list_state: ListState,
     pub cat_area: Rect,
     pub sub_area: Rect,
     pub tree_area: Rect,
+    /// The rows of the EXCLUDED list inside SUMMARY.
+    pub exclude_area: Rect,
     pub status: String,
 }
 
@@ -163,67 +227,33 @@ impl AppState {
         self.tree_list_state.selected().unwrap_or(0)
     }
 
-    pub fn set_tree_cursor(&mut self, idx: usize) {
-        self.tree_list_state.select(Some(idx));
-    }
-
-    /// 2 control rows + optional gitignore row + category rows.
-    pub fn cat_list_len(&self) -> usize {
-        2 + self.categories.len() + usize::from(self.has_gitignore)
+    /// Rows of the PREVIEW TREE: the expanded tree, or only the matches of
+    /// an active search and their folders.
+    pub fn visible_items(&self) -> Vec<scanner::FlatItem> {
+        match self.search.as_ref().filter(|s| !s.query.is_empty()) {
+            Some(search) => {
+                scanner::flatten_matching(&self.tree, &|item| search.hit(item).is_some())
+            }
+            None => scanner::flatten_visible(&self.tree),
+        }
     }
 
-    pub fn are_all_selected(&self) -> bool {
-        self.categories.iter().all(|c| c.enabled)
+    pub fn set_tree_cursor(&mut self, idx: usize) {
+        self.tree_list_state.select(Some(idx));
     }
 
-    pub fn selected_paths(&self) -> Vec<PathBuf> {
-        let mut result: Vec<PathBuf> = self
-            .categories
-            .iter()
-            .filter(|c| c.enabled)
-            .flat_map(|c| c.files.iter().cloned())
-            .collect();
-
-        if !self.subsystems.is_empty() {
-            let sub_set: HashSet<&PathBuf> = self
-                .subsystems
-                .iter()
-                .filter(|s| s.enabled)
-                .flat_map(|s| s.files.iter())
-                .collect();
-            result.retain(|p| sub_set.contains(p));
-        }
-
-        result.retain(|p| !self.exclude.contains(p.as_path()));
-
-        if self.respect_gitignore && self.gitignore_filter.has_filter() {
-            result.retain(|p| !self.gitignore_filter.is_ignored(&self.root, p));
-        }
-
-        for path in &self.manual_includes {
-            if !self.exclude.contains(path.as_path()) {
-                result.push(path.clone());
-            }
-        }
-
-        result.sort();
-        result.dedup();
-        result
+    /// Token count for one file, or `None` for paths that were never scanned.
+    pub fn file_tokens(&self, rel: &Path) -> Option<usize> {
+        let size = *self.file_sizes.get(rel)?;
+        Some(self.tokens.count_file(&self.root, rel, size))
     }
 
-    pub fn total_tokens(&self) -> usize {
-        self.selected_paths()
-            .iter()
-            .filter_map(|p| self.file_sizes.get(p))
-            .map(|&s| usize::try_from(s / 3).unwrap_or(usize::MAX / 3))
-            .sum()
+    pub fn tokens_for(&self, files: &[PathBuf]) -> usize {
+        files.iter().filter_map(|p| self.file_tokens(p)).sum()
     }
 
-    pub fn total_bytes(&self) -> u64 {
-        self.selected_paths()
-            .iter()
-            .filter_map(|p| self.file_sizes.get(p))
-            .sum()
+    pub fn wants_outline(&self, path: &Path) -> bool {
+        self.outline && !self.full_files.iter().any(|p| path.starts_with(p))
     }
 
     pub fn clamp_cursors(&mut self) {